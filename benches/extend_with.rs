@@ -0,0 +1,50 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use untyped_bytes::UntypedBytes;
+
+// Grid positions, computed per element.
+fn position(i: usize) -> [f32; 2] {
+    [(i % 256) as f32, (i / 256) as f32]
+}
+
+fn extend_with(c: &mut Criterion) {
+    let mut group = c.benchmark_group("extend_with");
+    for &count in &[64usize, 4096, 262144] {
+        group.bench_with_input(
+            BenchmarkId::new("extend_with", count),
+            &count,
+            |b, &count| {
+                b.iter(|| {
+                    let mut bytes = UntypedBytes::new();
+                    bytes.extend_with(black_box(count), position);
+                    bytes
+                })
+            },
+        );
+        group.bench_with_input(BenchmarkId::new("push", count), &count, |b, &count| {
+            b.iter(|| {
+                let mut bytes = UntypedBytes::new();
+                for i in 0..black_box(count) {
+                    bytes.push(position(i));
+                }
+                bytes
+            })
+        });
+        group.bench_with_input(
+            BenchmarkId::new("push/reserved", count),
+            &count,
+            |b, &count| {
+                b.iter(|| {
+                    let mut bytes = UntypedBytes::with_capacity(count * 8);
+                    for i in 0..black_box(count) {
+                        bytes.push(position(i));
+                    }
+                    bytes
+                })
+            },
+        );
+    }
+    group.finish();
+}
+
+criterion_group!(benches, extend_with);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion, Throughput};
+use untyped_bytes::UntypedBytes;
+
+// Equal buffers, so both comparisons have to look at every byte.
+fn buffers(len: usize) -> (UntypedBytes, Vec<u8>) {
+    let data: Vec<u8> = (0..len).map(|i| (i * 31 % 251) as u8).collect();
+    (UntypedBytes::from_slice(&data[..]), data)
+}
+
+fn fast_eq(c: &mut Criterion) {
+    let mut group = c.benchmark_group("fast_eq");
+    for &len in &[4096usize, 1 << 20, 16 << 20] {
+        let (bytes, other) = buffers(len);
+        group.throughput(Throughput::Bytes(len as u64));
+        group.bench_with_input(BenchmarkId::new("fast_eq", len), &len, |b, _| {
+            b.iter(|| black_box(&bytes).fast_eq(black_box(&other)))
+        });
+        group.bench_with_input(BenchmarkId::new("naive", len), &len, |b, _| {
+            b.iter(|| unsafe { black_box(&bytes).as_slice() } == black_box(&other[..]))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, fast_eq);
+criterion_main!(benches);
@@ -0,0 +1,58 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use untyped_bytes::{GrowthPolicy, UntypedBytes};
+
+// A 32 byte per-cell record, repeated once per cell of an instanced grid.
+fn template() -> UntypedBytes {
+    UntypedBytes::from_slice([[0.5f32, 1.5, 2.5, 1.0], [0.25, 0.75, 0.0, 1.0]])
+}
+
+fn naive(template: &UntypedBytes, count: usize, mut out: UntypedBytes) -> UntypedBytes {
+    for _ in 0..count {
+        out.extend_from_view(template.as_view());
+    }
+    out
+}
+
+fn repeat(c: &mut Criterion) {
+    let template = template();
+    let mut group = c.benchmark_group("repeat");
+    for &count in &[16usize, 1024, 65536] {
+        group.bench_with_input(BenchmarkId::new("doubling", count), &count, |b, &count| {
+            b.iter(|| black_box(&template).repeat(count))
+        });
+        group.bench_with_input(
+            BenchmarkId::new("naive/reserved", count),
+            &count,
+            |b, &count| {
+                b.iter(|| {
+                    let out = UntypedBytes::with_capacity(template.len() * count);
+                    naive(black_box(&template), count, out)
+                })
+            },
+        );
+        let policies = [
+            ("double", GrowthPolicy::Double),
+            ("exact", GrowthPolicy::Exact),
+            ("chunk-4k", GrowthPolicy::FixedChunk(4096)),
+        ];
+        for &(name, policy) in &policies {
+            group.bench_with_input(
+                BenchmarkId::new(format!("naive/{}", name), count),
+                &count,
+                |b, &count| {
+                    b.iter(|| {
+                        naive(
+                            black_box(&template),
+                            count,
+                            UntypedBytes::with_growth(policy),
+                        )
+                    })
+                },
+            );
+        }
+    }
+    group.finish();
+}
+
+criterion_group!(benches, repeat);
+criterion_main!(benches);
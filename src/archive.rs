@@ -0,0 +1,119 @@
+use crate::UntypedBytes;
+use alloc::vec::Vec;
+use rkyv::{
+    bytecheck::CheckBytes,
+    rancor::{Fallible, Source},
+    ser::{Allocator, Writer},
+    vec::{ArchivedVec, VecResolver},
+    Archive, Deserialize, Place, Portable, Serialize,
+};
+
+/// The archived form of `UntypedBytes`, a relative pointer to the bytes and their length.
+#[derive(CheckBytes, Portable)]
+#[bytecheck(crate = rkyv::bytecheck)]
+#[rkyv(crate = rkyv)]
+#[repr(transparent)]
+pub struct ArchivedUntypedBytes {
+    bytes: ArchivedVec<u8>,
+}
+
+impl ArchivedUntypedBytes {
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns the archived bytes, without copying. Unsafe to inspect in the presence of padding
+    /// bytes, as with `UntypedBytes::as_slice`.
+    pub unsafe fn as_bytes(&self) -> &[u8] {
+        self.bytes.as_slice()
+    }
+}
+
+impl Archive for UntypedBytes {
+    type Archived = ArchivedUntypedBytes;
+    type Resolver = VecResolver;
+
+    fn resolve(&self, resolver: Self::Resolver, out: Place<Self::Archived>) {
+        // `ArchivedUntypedBytes` is a transparent wrapper around `ArchivedVec<u8>`.
+        let out = unsafe { out.cast_unchecked::<ArchivedVec<u8>>() };
+        ArchivedVec::resolve_from_slice(&self.bytes, resolver, out);
+    }
+}
+
+impl<S: Fallible + Allocator + Writer + ?Sized> Serialize<S> for UntypedBytes {
+    fn serialize(&self, serializer: &mut S) -> Result<Self::Resolver, S::Error> {
+        ArchivedVec::<u8>::serialize_from_slice(&self.bytes, serializer)
+    }
+}
+
+impl<D: Fallible + ?Sized> Deserialize<UntypedBytes, D> for ArchivedUntypedBytes
+where
+    D::Error: Source,
+{
+    fn deserialize(&self, deserializer: &mut D) -> Result<UntypedBytes, D::Error> {
+        let bytes: Vec<u8> = self.bytes.deserialize(deserializer)?;
+        Ok(UntypedBytes::from_byte_vec(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rkyv::rancor::Error;
+
+    fn round_trip(bytes: &UntypedBytes) {
+        let archived = rkyv::to_bytes::<Error>(bytes).unwrap();
+        let view = rkyv::access::<ArchivedUntypedBytes, Error>(&archived).unwrap();
+        assert_eq!(view.len(), bytes.len());
+        assert_eq!(view.is_empty(), bytes.is_empty());
+        assert_eq!(unsafe { view.as_bytes() }, &bytes.bytes[..]);
+        let back = rkyv::from_bytes::<UntypedBytes, Error>(&archived).unwrap();
+        assert_eq!(back.bytes, bytes.bytes);
+    }
+
+    #[test]
+    fn round_trip_empty() {
+        round_trip(&UntypedBytes::new());
+    }
+
+    #[test]
+    fn round_trip_small() {
+        round_trip(&UntypedBytes::from_slice([1.0f32, 2.0, 3.0]));
+    }
+
+    #[test]
+    fn round_trip_multi_megabyte() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_with(1 << 20, |i| i as u32);
+        round_trip(&bytes);
+    }
+
+    #[derive(rkyv::Archive, rkyv::Serialize, rkyv::Deserialize)]
+    #[rkyv(crate = rkyv)]
+    struct Snapshot {
+        id: u32,
+        vertices: UntypedBytes,
+    }
+
+    #[test]
+    fn round_trip_as_a_field() {
+        let snapshot = Snapshot {
+            id: 7,
+            vertices: UntypedBytes::from_slice([[0.5f32; 3]; 4]),
+        };
+        let archived = rkyv::to_bytes::<Error>(&snapshot).unwrap();
+        let view = rkyv::access::<ArchivedSnapshot, Error>(&archived).unwrap();
+        assert_eq!(view.id, 7);
+        assert_eq!(
+            unsafe { view.vertices.as_bytes() },
+            &snapshot.vertices.bytes[..]
+        );
+        let back = rkyv::from_bytes::<Snapshot, Error>(&archived).unwrap();
+        assert_eq!(back.id, 7);
+        assert_eq!(back.vertices.bytes, snapshot.vertices.bytes);
+    }
+}
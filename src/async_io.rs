@@ -0,0 +1,145 @@
+use crate::UntypedBytes;
+use std::{
+    io,
+    pin::Pin,
+    task::{Context, Poll},
+};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+// The most that is reserved ahead of a single read, so a large `max` does not reserve memory the
+// stream may never fill.
+const READ_CHUNK: usize = 8 * 1024;
+
+impl UntypedBytes {
+    /// Writes all of the backing bytes to `w`.
+    pub async fn write_to_async<W: AsyncWrite + Unpin>(&self, w: &mut W) -> io::Result<()> {
+        w.write_all(&self.bytes).await
+    }
+
+    /// Appends up to `max` bytes read from `r`, reading directly into the spare capacity of the
+    /// buffer. Space is reserved a chunk at a time according to the buffer's `GrowthPolicy`.
+    /// Stops early at end of stream, and returns the number of bytes appended.
+    pub async fn extend_from_async_reader<R: AsyncRead + Unpin>(
+        &mut self,
+        r: &mut R,
+        max: usize,
+    ) -> io::Result<usize> {
+        let mut r = r.take(max as u64);
+        let mut total = 0;
+        loop {
+            if total == max {
+                return Ok(total);
+            }
+            // `read_buf` only grows the `Vec` itself when there is no spare capacity left.
+            self.reserve((max - total).min(READ_CHUNK));
+            let n = r.read_buf(&mut self.bytes).await?;
+            if n == 0 {
+                return Ok(total);
+            }
+            total += n;
+        }
+    }
+}
+
+/// Appends everything written, and is always ready.
+impl AsyncWrite for UntypedBytes {
+    fn poll_write(
+        self: Pin<&mut Self>,
+        _cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<io::Result<usize>> {
+        self.get_mut().extend_from_slice(buf);
+        Poll::Ready(Ok(buf.len()))
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_shutdown(self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::GrowthPolicy;
+    use tokio::io::duplex;
+
+    fn payload() -> UntypedBytes {
+        let mut bytes = UntypedBytes::new();
+        for i in 0..2500u32 {
+            bytes.push(i.wrapping_mul(0x9e37_79b9));
+        }
+        bytes
+    }
+
+    // The duplex buffer is much smaller than the payload, so both sides go through many chunks.
+    #[tokio::test]
+    async fn duplex_round_trip() {
+        let source = payload();
+        let (mut client, mut server) = duplex(64);
+        let mut received = UntypedBytes::from_slice([0xffu8]);
+        let (written, read) = tokio::join!(
+            async {
+                source.write_to_async(&mut client).await?;
+                drop(client);
+                Ok::<_, io::Error>(())
+            },
+            received.extend_from_async_reader(&mut server, usize::MAX),
+        );
+        written.unwrap();
+        assert_eq!(read.unwrap(), source.len());
+        assert_eq!(unsafe { received.as_slice()[0] }, 0xff);
+        assert_eq!(unsafe { &received.as_slice()[1..] }, unsafe {
+            source.as_slice()
+        });
+    }
+
+    #[tokio::test]
+    async fn reader_stops_at_max() {
+        let source = payload();
+        // Large enough to take the whole payload, so the write completes without a reader.
+        let (mut client, mut server) = duplex(source.len());
+        source.write_to_async(&mut client).await.unwrap();
+        let mut received = UntypedBytes::new();
+        let read = received.extend_from_async_reader(&mut server, 1000).await;
+        assert_eq!(read.unwrap(), 1000);
+        assert_eq!(unsafe { received.as_slice() }, unsafe {
+            &source.as_slice()[..1000]
+        });
+    }
+
+    #[tokio::test]
+    async fn reader_honors_growth_policy() {
+        let source = payload();
+        let mut exact = UntypedBytes::with_growth(GrowthPolicy::Exact);
+        let read = exact
+            .extend_from_async_reader(&mut unsafe { source.as_slice() }, source.len())
+            .await;
+        assert_eq!(read.unwrap(), source.len());
+        assert_eq!(exact.capacity(), source.len());
+
+        let mut chunked = UntypedBytes::with_growth(GrowthPolicy::FixedChunk(4096));
+        let read = chunked
+            .extend_from_async_reader(&mut unsafe { source.as_slice() }, usize::MAX)
+            .await;
+        assert_eq!(read.unwrap(), source.len());
+        assert!(chunked.capacity().is_multiple_of(4096));
+        assert_eq!(unsafe { chunked.as_slice() }, unsafe { source.as_slice() });
+    }
+
+    #[tokio::test]
+    async fn async_write_appends() {
+        let source = payload();
+        let mut sink = UntypedBytes::from_slice([7u8]);
+        sink.write_all(unsafe { source.as_slice() }).await.unwrap();
+        sink.flush().await.unwrap();
+        sink.shutdown().await.unwrap();
+        assert_eq!(sink.len(), source.len() + 1);
+        assert_eq!(unsafe { &sink.as_slice()[1..] }, unsafe {
+            source.as_slice()
+        });
+    }
+}
@@ -0,0 +1,251 @@
+// Bit-granular fields, packed LSB-first: stream bit `p` is bit `p % 8` (counting from the least
+// significant bit) of byte `p / 8`, and each field is written starting from its least significant
+// bit. This is the bit order used by DEFLATE.
+
+use crate::{UntypedBytes, UntypedSlice};
+
+fn check_nbits(nbits: u32) {
+    assert!(
+        (1..=64).contains(&nbits),
+        "bit fields must be between 1 and 64 bits wide, got {}",
+        nbits
+    );
+}
+
+fn mask(nbits: u32) -> u64 {
+    u64::MAX >> (64 - nbits)
+}
+
+/// Appends bit fields to an `UntypedBytes`, created by `UntypedBytes::bit_writer`.
+///
+/// Bits are packed LSB-first: the first field starts at the least significant bit of the first
+/// byte, and each field is written starting from its own least significant bit, spilling into
+/// the low bits of the next byte once a byte is full. For example writing `0b101` in 3 bits and
+/// then `0x3f` in 6 bits produces the bytes `[0xfd, 0x01]`.
+///
+/// Whole bytes are appended as they fill up. The partial final byte is zero padded and appended
+/// by `finish`, or when the writer is dropped.
+#[derive(Debug)]
+pub struct BitWriter<'a> {
+    bytes: &'a mut UntypedBytes,
+    // Holds fewer than 8 bits between calls, so a 64-bit field always fits.
+    pending: u128,
+    pending_bits: u32,
+    bits_written: usize,
+}
+
+impl UntypedBytes {
+    /// Returns a writer appending bit fields to the end of the buffer. See `BitWriter`.
+    pub fn bit_writer(&mut self) -> BitWriter<'_> {
+        BitWriter {
+            bytes: self,
+            pending: 0,
+            pending_bits: 0,
+            bits_written: 0,
+        }
+    }
+
+    /// Returns a reader over the backing bytes. See `BitReader`.
+    pub fn bit_reader(&self) -> BitReader<'_> {
+        BitReader::new(self.as_view())
+    }
+}
+
+impl<'a> UntypedSlice<'a> {
+    /// Returns a reader over the viewed bytes. See `BitReader`.
+    pub fn bit_reader(&self) -> BitReader<'a> {
+        BitReader::new(*self)
+    }
+}
+
+impl BitWriter<'_> {
+    /// Writes the low `nbits` bits of `value`, ignoring the rest. Panics if `nbits` is 0 or
+    /// greater than 64.
+    pub fn write_bits(&mut self, value: u64, nbits: u32) {
+        check_nbits(nbits);
+        self.pending |= ((value & mask(nbits)) as u128) << self.pending_bits;
+        self.pending_bits += nbits;
+        self.bits_written += nbits as usize;
+        let full = (self.pending_bits / 8) as usize;
+        if full > 0 {
+            self.bytes
+                .extend_from_slice(&self.pending.to_le_bytes()[..full]);
+            self.pending >>= full * 8;
+            self.pending_bits %= 8;
+        }
+    }
+
+    /// Zero pads up to the next byte boundary, appending the partial byte if there is one.
+    pub fn align_to_byte(&mut self) {
+        if self.pending_bits > 0 {
+            self.bytes.push(self.pending as u8);
+            self.bits_written += (8 - self.pending_bits) as usize;
+            self.pending = 0;
+            self.pending_bits = 0;
+        }
+    }
+
+    /// The number of bits written by this writer, including padding from `align_to_byte`.
+    pub fn bits_written(&self) -> usize {
+        self.bits_written
+    }
+
+    /// Appends the partial final byte, zero padded, and returns the number of bytes appended by
+    /// this writer.
+    pub fn finish(mut self) -> usize {
+        self.align_to_byte();
+        self.bits_written / 8
+    }
+}
+
+impl Drop for BitWriter<'_> {
+    fn drop(&mut self) {
+        self.align_to_byte()
+    }
+}
+
+/// Reads bit fields written by a `BitWriter`, in the same LSB-first order.
+#[derive(Clone, Copy, Debug)]
+pub struct BitReader<'a> {
+    bytes: &'a [u8],
+    position: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(view: UntypedSlice<'a>) -> Self {
+        BitReader {
+            bytes: unsafe { view.as_slice() },
+            position: 0,
+        }
+    }
+
+    /// Reads the next `nbits` bits into the low bits of the result, or returns `None` without
+    /// consuming anything if fewer than `nbits` bits remain. Panics if `nbits` is 0 or greater
+    /// than 64.
+    pub fn read_bits(&mut self, nbits: u32) -> Option<u64> {
+        check_nbits(nbits);
+        if nbits as usize > self.remaining_bits() {
+            return None;
+        }
+        let start = self.position / 8;
+        let end = (self.position + nbits as usize).div_ceil(8);
+        let mut raw = [0; 16];
+        raw[..end - start].copy_from_slice(&self.bytes[start..end]);
+        let value = (u128::from_le_bytes(raw) >> (self.position % 8)) as u64 & mask(nbits);
+        self.position += nbits as usize;
+        Some(value)
+    }
+
+    /// Skips ahead to the next byte boundary.
+    pub fn align_to_byte(&mut self) {
+        self.position = self.position.div_ceil(8) * 8
+    }
+
+    /// The number of bits read so far, including any skipped by `align_to_byte`.
+    pub fn bits_read(&self) -> usize {
+        self.position
+    }
+
+    pub fn remaining_bits(&self) -> usize {
+        self.bytes.len() * 8 - self.position
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    // The example from the `BitWriter` docs.
+    #[test]
+    fn known_byte_sequence() {
+        let mut bytes = UntypedBytes::new();
+        let mut writer = bytes.bit_writer();
+        writer.write_bits(0b101, 3);
+        writer.write_bits(0x3f, 6);
+        assert_eq!(writer.bits_written(), 9);
+        assert_eq!(writer.finish(), 2);
+        assert_eq!(unsafe { bytes.as_slice() }, &[0xfd, 0x01]);
+
+        let mut reader = bytes.bit_reader();
+        assert_eq!(reader.read_bits(3), Some(0b101));
+        assert_eq!(reader.read_bits(6), Some(0x3f));
+        assert_eq!(reader.remaining_bits(), 7);
+        assert_eq!(reader.read_bits(7), Some(0));
+        assert_eq!(reader.read_bits(1), None);
+    }
+
+    #[test]
+    fn high_bits_are_ignored() {
+        let mut bytes = UntypedBytes::new();
+        let mut writer = bytes.bit_writer();
+        writer.write_bits(u64::MAX, 4);
+        writer.write_bits(0, 4);
+        drop(writer);
+        assert_eq!(unsafe { bytes.as_slice() }, &[0x0f]);
+    }
+
+    // Fields of every width from every starting bit, read back in order.
+    #[test]
+    fn round_trip_across_byte_boundaries() {
+        let mut fields = Vec::new();
+        let mut state = 0x9e37_79b9_7f4a_7c15u64;
+        for nbits in 1..=64 {
+            for _ in 0..3 {
+                state ^= state << 13;
+                state ^= state >> 7;
+                state ^= state << 17;
+                fields.push((state & mask(nbits), nbits));
+            }
+        }
+        let mut bytes = UntypedBytes::from_slice([0xaau8]);
+        let mut writer = bytes.bit_writer();
+        for &(value, nbits) in &fields {
+            writer.write_bits(value, nbits);
+        }
+        let total: usize = fields.iter().map(|&(_, nbits)| nbits as usize).sum();
+        assert_eq!(writer.bits_written(), total);
+        assert_eq!(writer.finish(), total.div_ceil(8));
+
+        let view = bytes.as_view().slice(1..);
+        let mut reader = view.bit_reader();
+        for &(value, nbits) in &fields {
+            assert_eq!(reader.read_bits(nbits), Some(value), "{} bits", nbits);
+        }
+        assert!(reader.remaining_bits() < 8);
+    }
+
+    #[test]
+    fn align_to_byte_pads_with_zeros() {
+        let mut bytes = UntypedBytes::new();
+        let mut writer = bytes.bit_writer();
+        writer.write_bits(1, 1);
+        writer.align_to_byte();
+        assert_eq!(writer.bits_written(), 8);
+        writer.align_to_byte();
+        assert_eq!(writer.bits_written(), 8);
+        writer.write_bits(0xabc, 12);
+        assert_eq!(writer.finish(), 3);
+        assert_eq!(unsafe { bytes.as_slice() }, &[0x01, 0xbc, 0x0a]);
+
+        let mut reader = bytes.bit_reader();
+        assert_eq!(reader.read_bits(1), Some(1));
+        reader.align_to_byte();
+        assert_eq!(reader.bits_read(), 8);
+        assert_eq!(reader.read_bits(12), Some(0xabc));
+    }
+
+    #[test]
+    #[should_panic(expected = "bit fields must be between 1 and 64 bits wide, got 0")]
+    fn zero_width_write_panics() {
+        UntypedBytes::new().bit_writer().write_bits(0, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit fields must be between 1 and 64 bits wide, got 65")]
+    fn wide_read_panics() {
+        UntypedBytes::from_slice([0u8; 16])
+            .bit_reader()
+            .read_bits(65);
+    }
+}
@@ -0,0 +1,127 @@
+use crate::{as_bytes, as_bytes_slice, PushBytes, UntypedBytes, UntypedSlice};
+use bumpalo::{collections::Vec, Bump};
+
+/// Untyped bytes stored in a `bumpalo` arena instead of the global heap, created by
+/// `UntypedBytes::from_bump`. Useful for transient per-frame buffers that are freed all at once
+/// when the arena is reset.
+#[derive(Debug)]
+pub struct BumpBytes<'bump> {
+    bytes: Vec<'bump, u8>,
+}
+
+impl UntypedBytes {
+    /// Creates an empty buffer that allocates from `bump`.
+    pub fn from_bump(bump: &Bump) -> BumpBytes<'_> {
+        BumpBytes {
+            bytes: Vec::new_in(bump),
+        }
+    }
+}
+
+impl<'bump> BumpBytes<'bump> {
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.clear()
+    }
+
+    pub fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        self.bytes.extend_from_slice(unsafe { as_bytes(&value) })
+    }
+
+    pub fn extend_from_slice<T: Copy + Send + Sync + 'static>(&mut self, values: &[T]) {
+        self.bytes
+            .extend_from_slice(unsafe { as_bytes_slice(values) })
+    }
+
+    /// See `UntypedBytes::as_slice`.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        UntypedSlice::new(&self.bytes)
+    }
+
+    /// See `UntypedBytes::cast`.
+    pub unsafe fn cast<T: Copy + Send + Sync + 'static>(&self) -> T {
+        self.as_view().cast()
+    }
+
+    /// Copies the bytes into a new buffer on the global heap, to outlive the arena.
+    pub fn to_untyped_bytes(&self) -> UntypedBytes {
+        self.as_view().to_owned()
+    }
+}
+
+impl PushBytes for BumpBytes<'_> {
+    #[inline]
+    fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        BumpBytes::push(self, value)
+    }
+
+    #[inline]
+    fn extend_from_slice<T: Copy + Send + Sync + 'static>(&mut self, values: &[T]) {
+        BumpBytes::extend_from_slice(self, values)
+    }
+
+    #[inline]
+    fn written(&self) -> usize {
+        self.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_and_read_back() {
+        let bump = Bump::new();
+        let mut bytes = UntypedBytes::from_bump(&bump);
+        bytes.push([1.0f32, 2.0]);
+        bytes.extend_from_slice(&[[3.0f32, 4.0], [5.0, 6.0]]);
+        assert_eq!(bytes.len(), 24);
+        assert_eq!(
+            unsafe { bytes.as_view().read_at::<[f32; 2]>(16) },
+            [5.0, 6.0]
+        );
+        assert_eq!(
+            unsafe { bytes.cast::<[[f32; 2]; 3]>() },
+            [[1.0, 2.0], [3.0, 4.0], [5.0, 6.0]]
+        );
+    }
+
+    #[test]
+    fn outlives_the_arena_as_a_copy() {
+        let owned = {
+            let bump = Bump::new();
+            let mut bytes = UntypedBytes::from_bump(&bump);
+            bytes.push(0xdead_beefu32);
+            bytes.to_untyped_bytes()
+        };
+        assert_eq!(unsafe { owned.cast::<u32>() }, 0xdead_beef);
+    }
+
+    #[test]
+    fn reuse_after_reset() {
+        let mut bump = Bump::new();
+        for frame in 0..3u32 {
+            let mut bytes = UntypedBytes::from_bump(&bump);
+            bytes.push(frame);
+            bytes.push(frame * 2);
+            assert_eq!(bytes.written(), 8);
+            assert_eq!(unsafe { bytes.cast::<[u32; 2]>() }, [frame, frame * 2]);
+            bytes.clear();
+            assert!(bytes.is_empty());
+            drop(bytes);
+            bump.reset();
+        }
+    }
+}
@@ -0,0 +1,279 @@
+use crate::UntypedBytes;
+use alloc::vec::Vec;
+use core::{mem, ops::Range};
+
+// Slices are compared a block at a time with `==`, which is a `memcmp`, and only the block that
+// differs is scanned bytewise.
+const BLOCK: usize = 64;
+
+// Returns the offset of the first byte at which `a` and `b` differ, treating the end of the
+// shorter one as a difference.
+fn first_difference(a: &[u8], b: &[u8]) -> Option<usize> {
+    let len = a.len().min(b.len());
+    let (a_prefix, b_prefix) = (&a[..len], &b[..len]);
+    let mut offset = 0;
+    for (a_block, b_block) in a_prefix.chunks(BLOCK).zip(b_prefix.chunks(BLOCK)) {
+        if a_block != b_block {
+            let position = a_block.iter().zip(b_block).position(|(a, b)| a != b);
+            return Some(offset + position.unwrap());
+        }
+        offset += a_block.len();
+    }
+    if a.len() != b.len() {
+        Some(len)
+    } else {
+        None
+    }
+}
+
+/// The result of `UntypedBytes::compare_as`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ElementDiff<T> {
+    /// The buffers hold the same bytes.
+    Equal,
+    /// The buffers have different lengths, given here in elements, rounded down.
+    LengthMismatch { len: usize, other_len: usize },
+    /// The element at `index` is the first to differ, holding `value` in `self` and `other` in
+    /// the other buffer.
+    Different { index: usize, value: T, other: T },
+}
+
+impl UntypedBytes {
+    /// Returns the offset of the first byte that differs between the two buffers, or `None` if
+    /// they are equal. If one buffer is a prefix of the other, this is the length of the shorter.
+    pub fn first_difference(&self, other: &UntypedBytes) -> Option<usize> {
+        first_difference(&self.bytes, &other.bytes)
+    }
+
+    /// Compares the two buffers as arrays of `T`, decoding only the first element that differs.
+    /// Buffers of different lengths are reported as a `LengthMismatch` without comparing their
+    /// contents. This is only safe if both buffers were created from values of type `T`. Panics if
+    /// the first difference lies in a trailing partial element.
+    pub unsafe fn compare_as<T: Copy + Send + Sync + 'static>(
+        &self,
+        other: &UntypedBytes,
+    ) -> ElementDiff<T> {
+        let size = mem::size_of::<T>();
+        assert_ne!(size, 0, "elements must not be zero-sized");
+        if self.len() != other.len() {
+            return ElementDiff::LengthMismatch {
+                len: self.len() / size,
+                other_len: other.len() / size,
+            };
+        }
+        match self.first_difference(other) {
+            None => ElementDiff::Equal,
+            Some(offset) => {
+                let index = offset / size;
+                ElementDiff::Different {
+                    index,
+                    value: self.as_view().read_at(index * size),
+                    other: other.as_view().read_at(index * size),
+                }
+            }
+        }
+    }
+
+    /// Returns the byte ranges that differ from `previous`, for uploading only what changed. The
+    /// buffers are compared in `granularity` byte blocks, each dirty block is reported whole, and
+    /// adjacent dirty blocks are merged, so every range starts on a multiple of `granularity`. If
+    /// the lengths differ, every block past the end of the shorter buffer is dirty, so when
+    /// `previous` is longer the last range may end past the end of `self`. Panics if
+    /// `granularity` is zero.
+    pub fn diff_ranges(&self, previous: &UntypedBytes, granularity: usize) -> Vec<Range<usize>> {
+        assert_ne!(
+            granularity, 0,
+            "`diff_ranges` requires a nonzero granularity"
+        );
+        let common = self.len().min(previous.len());
+        let len = self.len().max(previous.len());
+        let mut ranges: Vec<Range<usize>> = Vec::new();
+        for start in (0..len).step_by(granularity) {
+            let end = (start + granularity).min(len);
+            let dirty = end > common || self.bytes[start..end] != previous.bytes[start..end];
+            if !dirty {
+                continue;
+            }
+            match ranges.last_mut() {
+                Some(last) if last.end == start => last.end = end,
+                _ => ranges.push(start..end),
+            }
+        }
+        ranges
+    }
+
+    /// Calls `apply` with the offset and contents of each range returned by `diff_ranges`, in
+    /// order, clipped to the end of `self`.
+    pub fn copy_diff_to(
+        &self,
+        previous: &UntypedBytes,
+        granularity: usize,
+        mut apply: impl FnMut(usize, &[u8]),
+    ) {
+        for range in self.diff_ranges(previous, granularity) {
+            let end = range.end.min(self.len());
+            if range.start < end {
+                apply(range.start, &self.bytes[range.start..end])
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+// `diff_ranges` results are compared against arrays of ranges, which are often single ranges.
+#[allow(clippy::single_range_in_vec_init)]
+mod tests {
+    use super::*;
+
+    fn samples(len: u32) -> UntypedBytes {
+        UntypedBytes::from_slice(&(0..len).map(|i| i as f32 * 0.5).collect::<Vec<_>>()[..])
+    }
+
+    #[test]
+    fn identical_buffers() {
+        let (a, b) = (samples(1000), samples(1000));
+        assert_eq!(a.first_difference(&b), None);
+        assert_eq!(unsafe { a.compare_as::<f32>(&b) }, ElementDiff::Equal);
+        assert_eq!(
+            unsafe { UntypedBytes::new().compare_as::<f32>(&UntypedBytes::new()) },
+            ElementDiff::Equal
+        );
+    }
+
+    #[test]
+    fn single_flipped_byte() {
+        let reference = samples(1000);
+        let mut capture = samples(1000);
+        // Flip the low bit of the second byte of element 600, well past the first block.
+        capture.bytes[600 * 4 + 1] ^= 1;
+        assert_eq!(reference.first_difference(&capture), Some(2401));
+        match unsafe { reference.compare_as::<f32>(&capture) } {
+            ElementDiff::Different {
+                index,
+                value,
+                other,
+            } => {
+                assert_eq!(index, 600);
+                assert_eq!(value, 300.0);
+                let mut flipped = 300.0f32.to_ne_bytes();
+                flipped[1] ^= 1;
+                assert_eq!(other.to_bits(), f32::from_ne_bytes(flipped).to_bits());
+            }
+            diff => panic!("expected a difference, got {:?}", diff),
+        }
+    }
+
+    #[test]
+    fn differences_at_the_edges() {
+        let reference = samples(64);
+        let mut first = samples(64);
+        first.bytes[0] ^= 0x80;
+        assert_eq!(reference.first_difference(&first), Some(0));
+        let mut last = samples(64);
+        last.bytes[255] ^= 0x80;
+        assert_eq!(reference.first_difference(&last), Some(255));
+        assert!(matches!(
+            unsafe { reference.compare_as::<f32>(&last) },
+            ElementDiff::Different { index: 63, .. }
+        ));
+    }
+
+    #[test]
+    fn mismatched_lengths() {
+        let (short, long) = (samples(10), samples(12));
+        assert_eq!(short.first_difference(&long), Some(40));
+        assert_eq!(long.first_difference(&short), Some(40));
+        assert_eq!(
+            unsafe { short.compare_as::<f32>(&long) },
+            ElementDiff::LengthMismatch {
+                len: 10,
+                other_len: 12
+            }
+        );
+        assert_eq!(
+            unsafe { long.compare_as::<[f32; 4]>(&UntypedBytes::new()) },
+            ElementDiff::LengthMismatch {
+                len: 3,
+                other_len: 0
+            }
+        );
+    }
+
+    fn bytes(len: usize) -> UntypedBytes {
+        UntypedBytes::from_slice(&(0..len).map(|i| i as u8).collect::<Vec<_>>()[..])
+    }
+
+    #[test]
+    fn diff_ranges_no_changes() {
+        let (previous, current) = (bytes(1000), bytes(1000));
+        assert!(current.diff_ranges(&previous, 4).is_empty());
+        assert!(current.diff_ranges(&previous, 256).is_empty());
+        let mut calls = 0;
+        current.copy_diff_to(&previous, 4, |_, _| calls += 1);
+        assert_eq!(calls, 0);
+    }
+
+    #[test]
+    fn diff_ranges_change_spanning_blocks() {
+        let previous = bytes(1024);
+        let mut current = bytes(1024);
+        // Bytes 254..258 straddle the boundary between the first two 256 byte blocks.
+        for byte in &mut current.bytes[254..258] {
+            *byte ^= 0xff;
+        }
+        assert_eq!(current.diff_ranges(&previous, 256), [0..512]);
+        assert_eq!(current.diff_ranges(&previous, 4), [252..260]);
+        // Separate changes are reported separately.
+        current.bytes[700] ^= 1;
+        assert_eq!(current.diff_ranges(&previous, 4), [252..260, 700..704]);
+    }
+
+    #[test]
+    fn diff_ranges_change_at_the_end() {
+        let previous = bytes(1001);
+        let mut current = bytes(1001);
+        current.bytes[1000] ^= 1;
+        // The last block is partial.
+        assert_eq!(current.diff_ranges(&previous, 4), [1000..1001]);
+        assert_eq!(current.diff_ranges(&previous, 256), [768..1001]);
+    }
+
+    #[test]
+    fn diff_ranges_differing_lengths() {
+        let (short, long) = (bytes(100), bytes(130));
+        assert_eq!(long.diff_ranges(&short, 16), [96..130]);
+        // When the previous buffer was longer, the range runs past the end of `self`.
+        assert_eq!(short.diff_ranges(&long, 16), [96..130]);
+        assert_eq!(UntypedBytes::new().diff_ranges(&short, 64), [0..100]);
+    }
+
+    #[test]
+    fn copy_diff_to_applies_changes() {
+        let previous = bytes(600);
+        let mut current = bytes(650);
+        current.bytes[10] = 0;
+        current.bytes[300] = 0;
+        let mut uploaded = unsafe { previous.as_slice() }.to_vec();
+        uploaded.resize(650, 0xee);
+        let mut offsets = Vec::new();
+        current.copy_diff_to(&previous, 64, |offset, data| {
+            offsets.push(offset);
+            uploaded[offset..offset + data.len()].copy_from_slice(data);
+        });
+        assert_eq!(offsets, [0, 256, 576]);
+        assert_eq!(&uploaded[..], unsafe { current.as_slice() });
+
+        // Ranges past the end of `self` are clipped, and dropped when nothing is left.
+        let mut calls = Vec::new();
+        bytes(64).copy_diff_to(&bytes(200), 64, |offset, data| {
+            calls.push((offset, data.len()))
+        });
+        assert!(calls.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "`diff_ranges` requires a nonzero granularity")]
+    fn diff_ranges_zero_granularity() {
+        bytes(4).diff_ranges(&bytes(4), 0);
+    }
+}
@@ -0,0 +1,227 @@
+use crate::{FixedWriter, PushBytes, UntypedBytes};
+use alloc::{boxed::Box, vec, vec::Vec};
+use core::{
+    mem::ManuallyDrop,
+    slice,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+/// A fixed-capacity buffer that many threads can append to at once.
+///
+/// Each call to `alloc` atomically bumps the length and hands out a `WriteRegion` that only the
+/// caller can write to. Once all regions are gone, `finish` turns the buffer into an ordinary
+/// `UntypedBytes`. Regions are laid out in allocation order, so the final layout is deterministic
+/// when the offsets are decided up front.
+///
+/// The storage is zeroed up front, so bytes a region never wrote, including those of a region
+/// that was leaked with `mem::forget`, read as zero.
+#[derive(Debug)]
+pub struct ConcurrentUntypedBytes {
+    ptr: *mut u8,
+    capacity: usize,
+    len: AtomicUsize,
+}
+
+unsafe impl Send for ConcurrentUntypedBytes {}
+unsafe impl Sync for ConcurrentUntypedBytes {}
+
+impl ConcurrentUntypedBytes {
+    pub fn with_capacity(capacity: usize) -> Self {
+        let bytes = Box::into_raw(vec![0u8; capacity].into_boxed_slice());
+        ConcurrentUntypedBytes {
+            ptr: bytes as *mut u8,
+            capacity,
+            len: AtomicUsize::new(0),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The number of bytes allocated so far.
+    pub fn len(&self) -> usize {
+        self.len.load(Ordering::Relaxed)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Claims the next `byte_len` bytes. Panics if the capacity would be exceeded.
+    pub fn alloc(&self, byte_len: usize) -> WriteRegion<'_> {
+        self.try_alloc(byte_len).unwrap_or_else(|| {
+            panic!(
+                "`ConcurrentUntypedBytes` overflow: allocating {} bytes with {} of {} in use",
+                byte_len,
+                self.len(),
+                self.capacity
+            )
+        })
+    }
+
+    /// Like `alloc`, but returns `None` if the capacity would be exceeded.
+    pub fn try_alloc(&self, byte_len: usize) -> Option<WriteRegion<'_>> {
+        let offset = self
+            .len
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |len| {
+                len.checked_add(byte_len)
+                    .filter(|&end| end <= self.capacity)
+            })
+            .ok()?;
+        // Every offset range is handed out exactly once, so the regions never alias.
+        let region = unsafe { slice::from_raw_parts_mut(self.ptr.add(offset), byte_len) };
+        Some(WriteRegion {
+            offset,
+            writer: FixedWriter::new(region),
+        })
+    }
+
+    pub fn finish(self) -> UntypedBytes {
+        let this = ManuallyDrop::new(self);
+        // No region can outlive the borrow of `self`, and all of the storage was initialized.
+        let bytes = unsafe { Vec::from_raw_parts(this.ptr, this.len(), this.capacity) };
+        UntypedBytes::from_byte_vec(bytes)
+    }
+}
+
+impl Drop for ConcurrentUntypedBytes {
+    fn drop(&mut self) {
+        drop(unsafe { Vec::from_raw_parts(self.ptr, self.capacity, self.capacity) })
+    }
+}
+
+/// A region of a `ConcurrentUntypedBytes` reserved by `alloc`. Any bytes left unwritten are zero.
+#[derive(Debug)]
+pub struct WriteRegion<'a> {
+    offset: usize,
+    writer: FixedWriter<'a>,
+}
+
+impl WriteRegion<'_> {
+    /// The offset of this region within the final buffer.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.writer.remaining()
+    }
+}
+
+impl PushBytes for WriteRegion<'_> {
+    #[inline]
+    fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        self.writer.push(value)
+    }
+
+    #[inline]
+    fn extend_from_slice<T: Copy + Send + Sync + 'static>(&mut self, values: &[T]) {
+        self.writer.extend_from_slice(values)
+    }
+
+    #[inline]
+    fn written(&self) -> usize {
+        self.writer.written()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::{mem, sync::Barrier, thread};
+
+    const THREADS: usize = 8;
+    const RECORDS: usize = 500;
+
+    // Each record is `[thread, index, thread ^ index]`, so a torn write shows up as a mismatch.
+    fn record(thread: usize, index: usize) -> [u32; 3] {
+        [thread as u32, index as u32, (thread ^ index) as u32]
+    }
+
+    #[test]
+    fn threaded_writes_match_serial_reference() {
+        let size = mem::size_of::<[u32; 3]>();
+        let concurrent = ConcurrentUntypedBytes::with_capacity(THREADS * RECORDS * size);
+        let barrier = Barrier::new(THREADS);
+        let offsets: Vec<Vec<usize>> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..THREADS)
+                .map(|thread| {
+                    let (concurrent, barrier) = (&concurrent, &barrier);
+                    scope.spawn(move || {
+                        barrier.wait();
+                        (0..RECORDS)
+                            .map(|index| {
+                                let mut region = concurrent.alloc(size);
+                                region.push(record(thread, index));
+                                assert_eq!(region.remaining(), 0);
+                                region.offset()
+                            })
+                            .collect()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).collect()
+        });
+        let bytes = concurrent.finish();
+        assert_eq!(bytes.len(), THREADS * RECORDS * size);
+
+        let mut reference = vec![0u8; bytes.len()];
+        for (thread, offsets) in offsets.iter().enumerate() {
+            for (index, &offset) in offsets.iter().enumerate() {
+                let raw: Vec<u8> = record(thread, index)
+                    .iter()
+                    .flat_map(|x| x.to_ne_bytes())
+                    .collect();
+                reference[offset..offset + size].copy_from_slice(&raw);
+            }
+        }
+        assert_eq!(unsafe { bytes.as_slice() }, &reference[..]);
+    }
+
+    #[test]
+    fn precomputed_sizes_give_deterministic_layout() {
+        let concurrent = ConcurrentUntypedBytes::with_capacity(64);
+        let regions: Vec<_> = [4, 8, 16].iter().map(|&n| concurrent.alloc(n)).collect();
+        let offsets: Vec<_> = regions.iter().map(WriteRegion::offset).collect();
+        assert_eq!(offsets, [0, 4, 12]);
+        thread::scope(|scope| {
+            for (i, mut region) in regions.into_iter().enumerate() {
+                scope.spawn(move || {
+                    while region.remaining() > 0 {
+                        region.push(i as u8 + 1)
+                    }
+                });
+            }
+        });
+        let bytes = concurrent.finish();
+        let mut expected = vec![1u8; 4];
+        expected.extend([2; 8]);
+        expected.extend([3; 16]);
+        assert_eq!(unsafe { bytes.as_slice() }, &expected[..]);
+    }
+
+    #[test]
+    #[allow(clippy::forget_non_drop)]
+    fn unwritten_and_leaked_regions_are_zero() {
+        let concurrent = ConcurrentUntypedBytes::with_capacity(16);
+        concurrent.alloc(8).push(0xffu8);
+        let mut leaked = concurrent.alloc(8);
+        leaked.push(0xeeu8);
+        mem::forget(leaked);
+        let bytes = concurrent.finish();
+        assert_eq!(
+            unsafe { bytes.as_slice() },
+            &[0xff, 0, 0, 0, 0, 0, 0, 0, 0xee, 0, 0, 0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn alloc_past_capacity_fails() {
+        let concurrent = ConcurrentUntypedBytes::with_capacity(8);
+        assert!(concurrent.try_alloc(6).is_some());
+        assert!(concurrent.try_alloc(3).is_none());
+        assert!(concurrent.try_alloc(2).is_some());
+        assert_eq!(concurrent.len(), 8);
+    }
+}
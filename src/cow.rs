@@ -0,0 +1,151 @@
+use crate::{UntypedBytes, UntypedSlice};
+use alloc::borrow::Cow;
+
+/// Either borrowed or owned untyped bytes, cloning the borrowed bytes only when mutation or
+/// ownership is required.
+#[derive(Clone, Debug)]
+pub enum UntypedCow<'a> {
+    Borrowed(UntypedSlice<'a>),
+    Owned(UntypedBytes),
+}
+
+impl<'a> UntypedCow<'a> {
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn len(&self) -> usize {
+        match self {
+            UntypedCow::Borrowed(view) => view.len(),
+            UntypedCow::Owned(bytes) => bytes.len(),
+        }
+    }
+
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        match self {
+            UntypedCow::Borrowed(view) => *view,
+            UntypedCow::Owned(bytes) => bytes.as_view(),
+        }
+    }
+
+    /// See `UntypedBytes::as_slice`.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        self.as_view().as_slice()
+    }
+
+    /// Returns the owned bytes, cloning the borrowed bytes if necessary.
+    pub fn into_owned(self) -> UntypedBytes {
+        match self {
+            UntypedCow::Borrowed(view) => view.to_owned(),
+            UntypedCow::Owned(bytes) => bytes,
+        }
+    }
+
+    /// Returns a mutable reference to the owned bytes, first promoting borrowed bytes to an owned
+    /// copy.
+    pub fn to_mut(&mut self) -> &mut UntypedBytes {
+        if let UntypedCow::Borrowed(view) = *self {
+            *self = UntypedCow::Owned(view.to_owned());
+        }
+        match self {
+            UntypedCow::Owned(bytes) => bytes,
+            UntypedCow::Borrowed(_) => unreachable!(),
+        }
+    }
+}
+
+impl<'a> From<UntypedSlice<'a>> for UntypedCow<'a> {
+    fn from(value: UntypedSlice<'a>) -> Self {
+        UntypedCow::Borrowed(value)
+    }
+}
+
+impl<'a> From<UntypedBytes> for UntypedCow<'a> {
+    fn from(value: UntypedBytes) -> Self {
+        UntypedCow::Owned(value)
+    }
+}
+
+// `From<Cow<[u8]>>` would overlap with the blanket `From<T: Copy>` impl, since coherence cannot
+// rule out `Cow` implementing `Copy`, so the conversion is an inherent constructor instead.
+impl UntypedBytes {
+    /// Borrows the backing bytes as a `Cow`, for APIs that accept either borrowed or owned bytes.
+    pub fn as_cow(&self) -> Cow<'_, [u8]> {
+        Cow::Borrowed(&self.bytes)
+    }
+
+    /// Copies borrowed bytes, and takes ownership of owned bytes without copying.
+    pub fn from_cow(value: Cow<'_, [u8]>) -> Self {
+        match value {
+            Cow::Borrowed(bytes) => UntypedBytes::from_slice(bytes),
+            Cow::Owned(bytes) => UntypedBytes::from_byte_vec(bytes),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    static BAKED: [u8; 4] = [1, 2, 3, 4];
+
+    // The borrowed variant keeps pointing at the static data until it has to be promoted.
+    #[test]
+    fn borrowed_does_not_copy_until_promoted() {
+        let mut cow = UntypedCow::from(UntypedSlice::from(&BAKED[..]));
+        assert_eq!(cow.len(), 4);
+        assert_eq!(unsafe { cow.as_slice() }.as_ptr(), BAKED.as_ptr());
+        let cloned = cow.clone();
+        assert_eq!(unsafe { cloned.as_slice() }.as_ptr(), BAKED.as_ptr());
+
+        cow.to_mut().push(5u8);
+        assert!(matches!(cow, UntypedCow::Owned(_)));
+        assert_ne!(unsafe { cow.as_slice() }.as_ptr(), BAKED.as_ptr());
+        assert_eq!(unsafe { cow.as_slice() }, &[1, 2, 3, 4, 5]);
+        assert_eq!(BAKED, [1, 2, 3, 4]);
+
+        let owned = cloned.into_owned();
+        assert_eq!(unsafe { owned.as_slice() }, &BAKED);
+    }
+
+    #[test]
+    fn owned_is_not_copied() {
+        let bytes = UntypedBytes::from_slice([1u32, 2]);
+        let ptr = unsafe { bytes.as_slice() }.as_ptr();
+        let mut cow = UntypedCow::from(bytes);
+        assert_eq!(unsafe { cow.to_mut().as_slice() }.as_ptr(), ptr);
+        assert_eq!(unsafe { cow.into_owned().as_slice() }.as_ptr(), ptr);
+    }
+
+    #[test]
+    fn empty_borrowed() {
+        let mut cow = UntypedCow::from(UntypedSlice::default());
+        assert!(cow.is_empty());
+        assert!(cow.to_mut().is_empty());
+    }
+
+    #[test]
+    fn as_cow_borrows() {
+        let bytes = UntypedBytes::from_slice([1u16, 2]);
+        let cow = bytes.as_cow();
+        assert!(matches!(cow, Cow::Borrowed(_)));
+        assert_eq!(cow.as_ptr(), unsafe { bytes.as_slice() }.as_ptr());
+        assert_eq!(cow.len(), 4);
+    }
+
+    #[test]
+    fn from_cow_borrowed_copies() {
+        let bytes = UntypedBytes::from_cow(Cow::Borrowed(&BAKED[..]));
+        assert_eq!(unsafe { bytes.as_slice() }, &BAKED);
+        assert_ne!(unsafe { bytes.as_slice() }.as_ptr(), BAKED.as_ptr());
+    }
+
+    #[test]
+    fn from_cow_owned_moves() {
+        let vec = BAKED.to_vec();
+        let ptr = vec.as_ptr();
+        let bytes = UntypedBytes::from_cow(Cow::Owned(vec));
+        assert_eq!(unsafe { bytes.as_slice() }, &BAKED);
+        assert_eq!(unsafe { bytes.as_slice() }.as_ptr(), ptr);
+    }
+}
@@ -0,0 +1,47 @@
+use crate::UntypedBytes;
+use core::ptr;
+
+impl UntypedBytes {
+    /// Compares the backing bytes of two buffers without short-circuiting on the first
+    /// difference. See `ct_eq_bytes`.
+    pub fn ct_eq(&self, other: &UntypedBytes) -> bool {
+        self.ct_eq_bytes(&other.bytes)
+    }
+
+    /// Compares the backing bytes with `other`. The lengths are compared first in variable time,
+    /// but the contents of equal length inputs are always compared in full, in constant time.
+    pub fn ct_eq_bytes(&self, other: &[u8]) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        let mut diff = 0u8;
+        for (a, b) in self.bytes.iter().zip(other) {
+            // Volatile reads keep the compiler from turning this into an early exit.
+            diff |= unsafe { ptr::read_volatile(a) ^ ptr::read_volatile(b) };
+        }
+        unsafe { ptr::read_volatile(&diff) == 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ct_eq_matches_eq() {
+        let tag = UntypedBytes::from_slice([0x0123_4567_89ab_cdefu64, 1]);
+        assert!(tag.ct_eq(&tag.clone()));
+        assert!(tag.ct_eq_bytes(unsafe { tag.as_slice() }));
+
+        let mut flipped = unsafe { tag.as_slice() }.to_vec();
+        flipped[15] ^= 1;
+        assert!(!tag.ct_eq_bytes(&flipped));
+        flipped[15] ^= 1;
+        flipped[0] ^= 0x80;
+        assert!(!tag.ct_eq_bytes(&flipped));
+
+        assert!(!tag.ct_eq_bytes(&flipped[..15]));
+        assert!(!tag.ct_eq(&UntypedBytes::new()));
+        assert!(UntypedBytes::new().ct_eq(&UntypedBytes::new()));
+    }
+}
@@ -0,0 +1,66 @@
+use crate::UntypedBytes;
+use digest::{Digest, Output};
+
+impl UntypedBytes {
+    /// Hashes the backing bytes with `D`.
+    ///
+    /// Padding bytes are hashed too, so the digest of values of a type with padding is not
+    /// canonical. Write such values with `PushFields`, which never writes padding, when the
+    /// digest is used for content addressing.
+    pub fn digest<D: Digest>(&self) -> Output<D> {
+        D::digest(&self.bytes)
+    }
+
+    /// Feeds the backing bytes into `hasher`, for hashing several buffers incrementally. See
+    /// `digest`.
+    pub fn update_digest<D: Digest>(&self, hasher: &mut D) {
+        hasher.update(&self.bytes)
+    }
+
+    /// The SHA-256 digest of the backing bytes. See `digest`.
+    #[cfg(feature = "sha2")]
+    pub fn sha256(&self) -> [u8; 32] {
+        self.digest::<sha2::Sha256>().into()
+    }
+}
+
+#[cfg(all(test, feature = "sha2"))]
+mod tests {
+    use super::*;
+    use sha2::Sha256;
+
+    fn hex(digest: &[u8]) -> alloc::string::String {
+        digest
+            .iter()
+            .map(|byte| alloc::format!("{:02x}", byte))
+            .collect()
+    }
+
+    #[test]
+    fn sha256_known_vectors() {
+        assert_eq!(
+            hex(&UntypedBytes::new().sha256()),
+            "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+        );
+        assert_eq!(
+            hex(&UntypedBytes::from_slice(*b"abc").sha256()),
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+        let long =
+            UntypedBytes::from_slice(*b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq");
+        assert_eq!(
+            hex(&long.digest::<Sha256>()),
+            "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+        );
+    }
+
+    #[test]
+    fn update_digest_across_buffers() {
+        let mut hasher = Sha256::new();
+        UntypedBytes::from_slice(*b"a").update_digest(&mut hasher);
+        UntypedBytes::new().update_digest(&mut hasher);
+        UntypedBytes::from_slice(*b"bc").update_digest(&mut hasher);
+        let digest: [u8; 32] = hasher.finalize().into();
+        assert_eq!(digest, UntypedBytes::from_slice(*b"abc").sha256());
+    }
+}
@@ -0,0 +1,73 @@
+/// Values that can be appended one at a time through `UntypedBytes`'s `Extend` impl.
+///
+/// `Extend` can't be implemented for every `Copy + Send + Sync + 'static` type, since that would
+/// include `&'static UntypedBytes`, which is concatenated rather than appended as a pointer. This
+/// is implemented for the primitives, and for arrays and tuples of up to 12 elements made of them.
+/// Implement it for your own plain data types to extend from iterators over them. With the
+/// `derive` feature, adding `Element` to the type's `#[derive(...)]` list writes the impl:
+///
+/// ```
+/// # use untyped_bytes::{Element, UntypedBytes};
+/// #[derive(Clone, Copy)]
+/// #[repr(C)]
+/// struct Vertex {
+///     position: [f32; 3],
+///     color: u32,
+/// }
+///
+/// impl Element for Vertex {}
+///
+/// let mut bytes = UntypedBytes::new();
+/// bytes.extend((0..4).map(|i| Vertex { position: [i as f32; 3], color: 0xffff_ffff }));
+/// assert_eq!(bytes.len(), 64);
+/// ```
+pub trait Element: Copy + Send + Sync + 'static {}
+
+macro_rules! impl_element {
+    ($($ty:ty),*) => {
+        $(impl Element for $ty {})*
+    };
+}
+
+impl_element!(
+    (),
+    bool,
+    char,
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64
+);
+
+impl<T: Element, const N: usize> Element for [T; N] {}
+
+macro_rules! impl_element_tuple {
+    ($(($($ty:ident),+))*) => {
+        $(impl<$($ty: Element),+> Element for ($($ty,)+) {})*
+    };
+}
+
+impl_element_tuple! {
+    (A)
+    (A, B)
+    (A, B, C)
+    (A, B, C, D)
+    (A, B, C, D, E)
+    (A, B, C, D, E, F)
+    (A, B, C, D, E, F, G)
+    (A, B, C, D, E, F, G, H)
+    (A, B, C, D, E, F, G, H, I)
+    (A, B, C, D, E, F, G, H, I, J)
+    (A, B, C, D, E, F, G, H, I, J, K)
+    (A, B, C, D, E, F, G, H, I, J, K, L)
+}
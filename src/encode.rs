@@ -0,0 +1,185 @@
+use crate::{PrefixedStrError, UntypedBytes};
+use core::{
+    convert::{TryFrom, TryInto},
+    str,
+};
+
+// The longest LEB128 encoding of a `u64`.
+const MAX_VARINT_LEN: usize = 10;
+
+impl UntypedBytes {
+    /// Appends `value` as an unsigned LEB128 varint of 1 to 10 bytes.
+    pub fn push_varint(&mut self, mut value: u64) {
+        loop {
+            let byte = (value & 0x7f) as u8;
+            value >>= 7;
+            if value == 0 {
+                self.push(byte);
+                return;
+            }
+            self.push(byte | 0x80);
+        }
+    }
+
+    /// Reads an unsigned LEB128 varint at `offset`, returning the value and its encoded length.
+    /// Returns `None` if the varint is truncated, longer than 10 bytes, or overflows a `u64`.
+    pub fn read_varint_at(&self, offset: usize) -> Option<(u64, usize)> {
+        let bytes = self.bytes.get(offset..)?;
+        let mut value = 0u64;
+        for (i, &byte) in bytes.iter().take(MAX_VARINT_LEN).enumerate() {
+            let bits = u64::from(byte & 0x7f);
+            if i == MAX_VARINT_LEN - 1 && bits > 1 {
+                return None;
+            }
+            value |= bits << (7 * i);
+            if byte & 0x80 == 0 {
+                return Some((value, i + 1));
+            }
+        }
+        None
+    }
+
+    /// Appends `s` as a big-endian `u32` byte length followed by its UTF-8 bytes.
+    pub fn push_str_prefixed(&mut self, s: &str) {
+        let len = u32::try_from(s.len()).expect("string is too long for a `u32` length prefix");
+        self.push(len.to_be_bytes());
+        self.extend_from_slice(s.as_bytes());
+    }
+
+    /// Reads a string written by `push_str_prefixed` at `offset`, returning it along with the
+    /// total number of bytes it occupies. Returns an error if the prefix or the string runs past
+    /// the end of the buffer, or if the string is not valid UTF-8.
+    pub fn read_str_prefixed_at(&self, offset: usize) -> Result<(&str, usize), PrefixedStrError> {
+        let truncated_prefix = PrefixedStrError::TruncatedPrefix {
+            offset,
+            len: self.len(),
+        };
+        let start = offset.checked_add(4).ok_or(truncated_prefix)?;
+        let prefix = self.bytes.get(offset..start).ok_or(truncated_prefix)?;
+        let str_len = u32::from_be_bytes(prefix.try_into().unwrap()) as usize;
+        let s = start
+            .checked_add(str_len)
+            .and_then(|end| self.bytes.get(start..end))
+            .ok_or(PrefixedStrError::TruncatedString {
+                start,
+                str_len,
+                len: self.len(),
+            })?;
+        Ok((str::from_utf8(s)?, 4 + str_len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn varint_round_trips() {
+        let values = [
+            0,
+            1,
+            127,
+            128,
+            300,
+            16_383,
+            16_384,
+            u32::MAX as u64,
+            u64::MAX,
+        ];
+        let lens = [1, 1, 1, 2, 2, 2, 3, 5, 10];
+        let mut bytes = UntypedBytes::new();
+        for &value in values.iter() {
+            bytes.push_varint(value);
+        }
+        let mut offset = 0;
+        for (&value, &len) in values.iter().zip(lens.iter()) {
+            assert_eq!(bytes.read_varint_at(offset), Some((value, len)));
+            offset += len;
+        }
+        assert_eq!(offset, bytes.len());
+        assert_eq!(bytes.read_varint_at(offset), None);
+    }
+
+    #[test]
+    fn varint_known_encodings() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push_varint(300);
+        assert_eq!(&bytes.bytes[..], &[0xac, 0x02]);
+        bytes.clear();
+        bytes.push_varint(u64::MAX);
+        assert_eq!(
+            &bytes.bytes[..],
+            &[0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0xff, 0x01]
+        );
+    }
+
+    #[test]
+    fn malformed_varints() {
+        // Never terminates within 10 bytes.
+        let endless = UntypedBytes::from_slice([0x80u8; 12]);
+        assert_eq!(endless.read_varint_at(0), None);
+        // Truncated mid-varint.
+        assert_eq!(
+            UntypedBytes::from_slice([0xffu8, 0xff]).read_varint_at(0),
+            None
+        );
+        // The tenth byte carries more than the one bit left in a `u64`.
+        let mut overflow = [0xffu8; 10];
+        overflow[9] = 0x02;
+        assert_eq!(UntypedBytes::from_slice(overflow).read_varint_at(0), None);
+        // Overlong but in range and terminated.
+        let padded = UntypedBytes::from_slice([0x81u8, 0x80, 0x00]);
+        assert_eq!(padded.read_varint_at(0), Some((1, 3)));
+    }
+
+    #[test]
+    fn prefixed_str_round_trip() {
+        let mut bytes = UntypedBytes::from_slice([0xffu8]);
+        bytes.push_str_prefixed("héllo");
+        bytes.push_str_prefixed("");
+        assert_eq!(&bytes.bytes[1..5], &[0, 0, 0, 6]);
+        assert_eq!(bytes.read_str_prefixed_at(1), Ok(("héllo", 10)));
+        assert_eq!(bytes.read_str_prefixed_at(11), Ok(("", 4)));
+    }
+
+    #[test]
+    fn prefixed_str_rejects_invalid_utf8() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push(3u32.to_be_bytes());
+        bytes.extend_from_slice([b'a', 0xc3, 0x28]);
+        match bytes.read_str_prefixed_at(0) {
+            Err(PrefixedStrError::InvalidUtf8(err)) => assert_eq!(err.valid_up_to(), 1),
+            other => panic!("expected invalid UTF-8, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn prefixed_str_rejects_truncated_input() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push_str_prefixed("abc");
+        assert_eq!(
+            bytes.read_str_prefixed_at(5),
+            Err(PrefixedStrError::TruncatedPrefix { offset: 5, len: 7 })
+        );
+        assert_eq!(
+            bytes.read_str_prefixed_at(usize::MAX),
+            Err(PrefixedStrError::TruncatedPrefix {
+                offset: usize::MAX,
+                len: 7
+            })
+        );
+        bytes.truncate_bytes(6);
+        assert_eq!(
+            bytes.read_str_prefixed_at(0),
+            Err(PrefixedStrError::TruncatedString {
+                start: 4,
+                str_len: 3,
+                len: 6
+            })
+        );
+
+        let mut huge = UntypedBytes::new();
+        huge.push(u32::MAX.to_be_bytes());
+        assert!(huge.read_str_prefixed_at(0).is_err());
+    }
+}
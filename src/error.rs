@@ -0,0 +1,247 @@
+use core::{fmt, str::Utf8Error};
+
+/// The error returned by fallible casts and reads out of untyped bytes.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CastError {
+    /// The number of bytes did not match the size of the requested type.
+    SizeMismatch { expected: usize, found: usize },
+    /// The number of bytes was not a multiple of the size of the requested element type.
+    NotAMultiple { element_size: usize, found: usize },
+    /// The bytes were not sufficiently aligned for the requested type.
+    AlignmentMismatch { required: usize, address: usize },
+    /// The requested value extends past the end of the bytes.
+    OutOfBounds {
+        offset: usize,
+        size: usize,
+        len: usize,
+    },
+}
+
+impl fmt::Display for CastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CastError::SizeMismatch { expected, found } => write!(
+                f,
+                "size mismatch: expected {} bytes, found {}",
+                expected, found
+            ),
+            CastError::NotAMultiple {
+                element_size,
+                found,
+            } => write!(
+                f,
+                "size mismatch: {} bytes is not a multiple of the element size {}",
+                found, element_size
+            ),
+            CastError::AlignmentMismatch { required, address } => write!(
+                f,
+                "alignment mismatch: address {:#x} is not aligned to {} bytes",
+                address, required
+            ),
+            CastError::OutOfBounds { offset, size, len } => write!(
+                f,
+                "out of bounds: {} bytes at offset {} exceeds the length {}",
+                size, offset, len
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CastError {}
+
+/// The error returned by `UntypedBytes::extend_from_indexed` when an index is out of range.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct IndexOutOfRange {
+    /// The position of the offending index within the index list.
+    pub position: usize,
+    /// The length of the source slice being indexed.
+    pub len: usize,
+}
+
+impl fmt::Display for IndexOutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "index at position {} is out of range for a source of length {}",
+            self.position, self.len
+        )
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for IndexOutOfRange {}
+
+/// The error returned by `UntypedBytes::extend_from_strided_slice` when the strided records do
+/// not fit in the source.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StrideError {
+    /// The last record ends past the end of the source.
+    OutOfBounds { required: usize, len: usize },
+    /// The extent of the records overflows `usize`.
+    Overflow,
+}
+
+impl fmt::Display for StrideError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StrideError::OutOfBounds { required, len } => write!(
+                f,
+                "out of bounds: strided records span {} bytes, but the source is {} bytes",
+                required, len
+            ),
+            StrideError::Overflow => write!(f, "the extent of the strided records overflows"),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for StrideError {}
+
+/// The error returned by `UntypedBytes::read_str_prefixed_at` for malformed input.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefixedStrError {
+    /// The 4 byte length prefix at `offset` extends past the end of the bytes.
+    TruncatedPrefix { offset: usize, len: usize },
+    /// The `str_len` bytes of string data starting at `start` extend past the end of the bytes.
+    TruncatedString {
+        start: usize,
+        str_len: usize,
+        len: usize,
+    },
+    /// The string data is not valid UTF-8.
+    InvalidUtf8(Utf8Error),
+}
+
+impl From<Utf8Error> for PrefixedStrError {
+    fn from(err: Utf8Error) -> Self {
+        PrefixedStrError::InvalidUtf8(err)
+    }
+}
+
+impl fmt::Display for PrefixedStrError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrefixedStrError::TruncatedPrefix { offset, len } => write!(
+                f,
+                "truncated length prefix: 4 bytes at offset {} exceeds the length {}",
+                offset, len
+            ),
+            PrefixedStrError::TruncatedString {
+                start,
+                str_len,
+                len,
+            } => write!(
+                f,
+                "truncated string: {} bytes at offset {} exceeds the length {}",
+                str_len, start, len
+            ),
+            PrefixedStrError::InvalidUtf8(err) => write!(f, "invalid UTF-8: {}", err),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PrefixedStrError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            PrefixedStrError::InvalidUtf8(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UntypedBytes;
+    use alloc::string::ToString;
+
+    #[test]
+    fn cast_error_display() {
+        let cases = [
+            (
+                CastError::SizeMismatch {
+                    expected: 4,
+                    found: 3,
+                },
+                "size mismatch: expected 4 bytes, found 3",
+            ),
+            (
+                CastError::NotAMultiple {
+                    element_size: 4,
+                    found: 6,
+                },
+                "size mismatch: 6 bytes is not a multiple of the element size 4",
+            ),
+            (
+                CastError::AlignmentMismatch {
+                    required: 4,
+                    address: 0x1001,
+                },
+                "alignment mismatch: address 0x1001 is not aligned to 4 bytes",
+            ),
+            (
+                CastError::OutOfBounds {
+                    offset: 6,
+                    size: 4,
+                    len: 8,
+                },
+                "out of bounds: 4 bytes at offset 6 exceeds the length 8",
+            ),
+        ];
+        for (err, message) in cases {
+            assert_eq!(err.to_string(), message);
+        }
+    }
+
+    #[test]
+    fn checked_reads_report_cast_errors() {
+        let bytes = UntypedBytes::from_slice([1u32, 2, 3]);
+        unsafe {
+            assert_eq!(
+                bytes.try_cast::<u64>(),
+                Err(CastError::SizeMismatch {
+                    expected: 8,
+                    found: 12
+                })
+            );
+            assert_eq!(
+                bytes.try_cast_vec::<u64>(),
+                Err(CastError::NotAMultiple {
+                    element_size: 8,
+                    found: 12
+                })
+            );
+            assert_eq!(bytes.try_read_at::<u32>(8), Ok(3));
+            assert_eq!(
+                bytes.try_read_at::<u32>(9),
+                Err(CastError::OutOfBounds {
+                    offset: 9,
+                    size: 4,
+                    len: 12
+                })
+            );
+            assert!(bytes.try_read_at::<u32>(usize::MAX).is_err());
+        }
+    }
+
+    #[test]
+    fn misaligned_slice_cast_reports_alignment() {
+        let bytes = UntypedBytes::from_slice([0u8; 20]);
+        let start = unsafe { bytes.as_slice() }.as_ptr().align_offset(4);
+        let aligned = bytes.view_range(start..start + 12);
+        let misaligned = aligned.slice(1..9);
+        unsafe {
+            assert_eq!(aligned.try_cast_slice::<u32>(), Ok(&[0u32; 3][..]));
+            match misaligned.try_cast_slice::<u32>() {
+                Err(CastError::AlignmentMismatch { required, address }) => {
+                    assert_eq!(required, 4);
+                    assert_eq!(address, aligned.as_slice().as_ptr() as usize + 1);
+                }
+                other => panic!("expected an alignment mismatch, got {:?}", other),
+            }
+            assert_eq!(UntypedBytes::new().try_cast_slice::<u32>(), Ok(&[][..]));
+        }
+    }
+}
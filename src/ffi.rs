@@ -0,0 +1,193 @@
+//! A C ABI for creating and filling `UntypedBytes` from code that does not link against this
+//! crate, such as native plugins.
+//!
+//! Buffers are opaque `UntypedBytes *` handles. Every live handle is tracked, so null, dangling,
+//! and already freed handles are rejected with an error code instead of being dereferenced.
+
+use crate::UntypedBytes;
+use std::{
+    collections::HashSet,
+    slice,
+    sync::{Mutex, MutexGuard, OnceLock},
+};
+
+/// The result of an FFI call.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum UntypedBytesStatus {
+    Ok = 0,
+    NullPointer = 1,
+    /// The handle was not created by `untyped_bytes_new`, or was already freed.
+    InvalidHandle = 2,
+}
+
+fn live_handles() -> MutexGuard<'static, HashSet<usize>> {
+    static HANDLES: OnceLock<Mutex<HashSet<usize>>> = OnceLock::new();
+    HANDLES
+        .get_or_init(Default::default)
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+fn register(bytes: UntypedBytes) -> *mut UntypedBytes {
+    let ptr = Box::into_raw(Box::new(bytes));
+    live_handles().insert(ptr as usize);
+    ptr
+}
+
+// Runs `f` on the buffer behind `ptr` while holding the handle lock, so the handle cannot be
+// freed concurrently.
+fn with_handle<R>(
+    ptr: *mut UntypedBytes,
+    f: impl FnOnce(&mut UntypedBytes) -> R,
+) -> Result<R, UntypedBytesStatus> {
+    if ptr.is_null() {
+        return Err(UntypedBytesStatus::NullPointer);
+    }
+    let handles = live_handles();
+    if !handles.contains(&(ptr as usize)) {
+        return Err(UntypedBytesStatus::InvalidHandle);
+    }
+    let result = f(unsafe { &mut *ptr });
+    drop(handles);
+    Ok(result)
+}
+
+fn unregister(ptr: *mut UntypedBytes) -> Result<Box<UntypedBytes>, UntypedBytesStatus> {
+    if ptr.is_null() {
+        return Err(UntypedBytesStatus::NullPointer);
+    }
+    if !live_handles().remove(&(ptr as usize)) {
+        return Err(UntypedBytesStatus::InvalidHandle);
+    }
+    Ok(unsafe { Box::from_raw(ptr) })
+}
+
+fn status(result: Result<(), UntypedBytesStatus>) -> UntypedBytesStatus {
+    result.err().unwrap_or(UntypedBytesStatus::Ok)
+}
+
+/// Creates an empty buffer. Must be released with `untyped_bytes_free`.
+#[no_mangle]
+pub extern "C" fn untyped_bytes_new() -> *mut UntypedBytes {
+    register(UntypedBytes::new())
+}
+
+/// Creates an empty buffer with room for `capacity` bytes. Must be released with
+/// `untyped_bytes_free`.
+#[no_mangle]
+pub extern "C" fn untyped_bytes_with_capacity(capacity: usize) -> *mut UntypedBytes {
+    register(UntypedBytes::with_capacity(capacity))
+}
+
+/// Appends `len` bytes read from `data`, which may only be null if `len` is zero.
+///
+/// `data` must be valid for reads of `len` bytes.
+#[no_mangle]
+pub unsafe extern "C" fn untyped_bytes_push_bytes(
+    ptr: *mut UntypedBytes,
+    data: *const u8,
+    len: usize,
+) -> UntypedBytesStatus {
+    if data.is_null() && len != 0 {
+        return UntypedBytesStatus::NullPointer;
+    }
+    status(with_handle(ptr, |bytes| {
+        if len != 0 {
+            bytes.extend_from_slice(slice::from_raw_parts(data, len))
+        }
+    }))
+}
+
+/// Returns the length in bytes, or `0` for an invalid handle.
+#[no_mangle]
+pub extern "C" fn untyped_bytes_len(ptr: *mut UntypedBytes) -> usize {
+    with_handle(ptr, |bytes| bytes.len()).unwrap_or(0)
+}
+
+/// Returns a pointer to the bytes, valid until the buffer is next modified or freed, or null for
+/// an invalid handle.
+#[no_mangle]
+pub extern "C" fn untyped_bytes_data(ptr: *mut UntypedBytes) -> *const u8 {
+    with_handle(ptr, |bytes| bytes.bytes.as_ptr()).unwrap_or(std::ptr::null())
+}
+
+#[no_mangle]
+pub extern "C" fn untyped_bytes_clear(ptr: *mut UntypedBytes) -> UntypedBytesStatus {
+    status(with_handle(ptr, UntypedBytes::clear))
+}
+
+/// Releases a buffer. Freeing the same handle twice returns `InvalidHandle`.
+#[no_mangle]
+pub extern "C" fn untyped_bytes_free(ptr: *mut UntypedBytes) -> UntypedBytesStatus {
+    status(unregister(ptr).map(drop))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::ptr;
+
+    #[test]
+    fn lifecycle() {
+        let handle = untyped_bytes_with_capacity(16);
+        let data = [1u8, 2, 3];
+        assert_eq!(
+            unsafe { untyped_bytes_push_bytes(handle, data.as_ptr(), data.len()) },
+            UntypedBytesStatus::Ok
+        );
+        assert_eq!(
+            unsafe { untyped_bytes_push_bytes(handle, ptr::null(), 0) },
+            UntypedBytesStatus::Ok
+        );
+        assert_eq!(untyped_bytes_len(handle), 3);
+        let contents = unsafe { slice::from_raw_parts(untyped_bytes_data(handle), 3) };
+        assert_eq!(contents, &data);
+        assert_eq!(untyped_bytes_clear(handle), UntypedBytesStatus::Ok);
+        assert_eq!(untyped_bytes_len(handle), 0);
+        assert_eq!(untyped_bytes_free(handle), UntypedBytesStatus::Ok);
+    }
+
+    #[test]
+    fn double_free_is_rejected() {
+        let handle = untyped_bytes_new();
+        assert_eq!(untyped_bytes_free(handle), UntypedBytesStatus::Ok);
+        assert_eq!(
+            untyped_bytes_free(handle),
+            UntypedBytesStatus::InvalidHandle
+        );
+        assert_eq!(untyped_bytes_len(handle), 0);
+        assert!(untyped_bytes_data(handle).is_null());
+        assert_eq!(
+            untyped_bytes_clear(handle),
+            UntypedBytesStatus::InvalidHandle
+        );
+        let data = 0u8;
+        assert_eq!(
+            unsafe { untyped_bytes_push_bytes(handle, &data, 1) },
+            UntypedBytesStatus::InvalidHandle
+        );
+    }
+
+    #[test]
+    fn null_and_foreign_pointers_are_rejected() {
+        let null = ptr::null_mut();
+        assert_eq!(untyped_bytes_free(null), UntypedBytesStatus::NullPointer);
+        assert_eq!(untyped_bytes_clear(null), UntypedBytesStatus::NullPointer);
+        assert_eq!(untyped_bytes_len(null), 0);
+        assert!(untyped_bytes_data(null).is_null());
+
+        let handle = untyped_bytes_new();
+        assert_eq!(
+            unsafe { untyped_bytes_push_bytes(handle, ptr::null(), 4) },
+            UntypedBytesStatus::NullPointer
+        );
+        assert_eq!(untyped_bytes_free(handle), UntypedBytesStatus::Ok);
+
+        let mut foreign = UntypedBytes::new();
+        assert_eq!(
+            untyped_bytes_free(&mut foreign),
+            UntypedBytesStatus::InvalidHandle
+        );
+    }
+}
@@ -0,0 +1,112 @@
+use crate::UntypedBytes;
+use flate2::{
+    read::{DeflateDecoder, GzDecoder},
+    write::{DeflateEncoder, GzEncoder},
+    Compression,
+};
+use std::io::{self, Read, Write};
+
+impl UntypedBytes {
+    /// Compresses the backing bytes into a new buffer in the gzip format.
+    pub fn compress_gzip(&self) -> io::Result<UntypedBytes> {
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&self.bytes)?;
+        encoder.finish().map(Self::from_byte_vec)
+    }
+
+    /// Compresses the backing bytes into a new buffer as a raw deflate stream.
+    pub fn compress_deflate(&self) -> io::Result<UntypedBytes> {
+        let mut encoder = DeflateEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(&self.bytes)?;
+        encoder.finish().map(Self::from_byte_vec)
+    }
+
+    /// Inflates gzip compressed bytes into a new buffer. Corrupt or truncated input is reported
+    /// as an `io::Error`.
+    pub fn decompress_gzip(&self) -> io::Result<UntypedBytes> {
+        let mut bytes = Vec::new();
+        GzDecoder::new(&self.bytes[..]).read_to_end(&mut bytes)?;
+        Ok(Self::from_byte_vec(bytes))
+    }
+
+    /// Inflates a raw deflate stream into a new buffer. Corrupt or truncated input is reported as
+    /// an `io::Error`.
+    pub fn decompress_deflate(&self) -> io::Result<UntypedBytes> {
+        let mut bytes = Vec::new();
+        DeflateDecoder::new(&self.bytes[..]).read_to_end(&mut bytes)?;
+        Ok(Self::from_byte_vec(bytes))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn compressible() -> UntypedBytes {
+        let mut bytes = UntypedBytes::new();
+        for i in 0..4096u32 {
+            bytes.push(i % 16);
+        }
+        bytes
+    }
+
+    #[test]
+    fn compress_shrinks_compressible_data() {
+        let bytes = compressible();
+        for compressed in [bytes.compress_gzip(), bytes.compress_deflate()] {
+            let compressed = compressed.unwrap();
+            assert!(!compressed.is_empty());
+            assert!(compressed.len() < bytes.len());
+            assert!(!bytes.starts_with(unsafe { compressed.as_slice() }));
+        }
+    }
+
+    #[test]
+    fn gzip_and_deflate_differ() {
+        let bytes = compressible();
+        let gzip = bytes.compress_gzip().unwrap();
+        let deflate = bytes.compress_deflate().unwrap();
+        // gzip wraps the deflate stream in a header and trailer.
+        assert_eq!(unsafe { &gzip.as_slice()[..2] }, &[0x1f, 0x8b]);
+        assert!(gzip.len() > deflate.len());
+    }
+
+    #[test]
+    fn round_trip() {
+        let payloads = [
+            UntypedBytes::new(),
+            UntypedBytes::from_slice(*b"x"),
+            UntypedBytes::from_slice(*b"hello, hello, hello, hello"),
+            compressible(),
+        ];
+        for bytes in &payloads {
+            let gzip = bytes.compress_gzip().unwrap().decompress_gzip().unwrap();
+            let deflate = bytes
+                .compress_deflate()
+                .unwrap()
+                .decompress_deflate()
+                .unwrap();
+            unsafe {
+                assert_eq!(gzip.as_slice(), bytes.as_slice());
+                assert_eq!(deflate.as_slice(), bytes.as_slice());
+            }
+        }
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        let mut gzip = compressible().compress_gzip().unwrap();
+        gzip.truncate_bytes(gzip.len() / 2);
+        assert!(gzip.decompress_gzip().is_err());
+        let mut deflate = compressible().compress_deflate().unwrap();
+        deflate.truncate_bytes(deflate.len() / 2);
+        assert!(deflate.decompress_deflate().is_err());
+    }
+
+    #[test]
+    fn corrupt_input_is_an_error() {
+        let garbage = UntypedBytes::from_slice(*b"not compressed at all");
+        assert!(garbage.decompress_gzip().is_err());
+        assert!(compressible().decompress_gzip().is_err());
+    }
+}
@@ -0,0 +1,118 @@
+// Conversions between `f32` and IEEE half precision, for GPU vertex attributes. Conversion goes
+// through fixed size chunks on the stack so `half` can use its vectorized slice conversions.
+
+use crate::{view, UntypedBytes};
+use alloc::vec::Vec;
+use core::{mem, ops::RangeBounds};
+use half::{f16, slice::HalfFloatSliceExt};
+
+const CHUNK: usize = 64;
+
+impl UntypedBytes {
+    /// Appends `value` as a native-endian `f16`. Values outside the range of `f16` become
+    /// infinities, and NaNs stay NaNs.
+    pub fn push_f16(&mut self, value: f32) {
+        self.push(f16::from_f32(value))
+    }
+
+    /// Appends every value as a native-endian `f16`, as with `push_f16`, reserving space for all
+    /// of them up front.
+    pub fn extend_f16_from_f32(&mut self, values: &[f32]) {
+        let size = values
+            .len()
+            .checked_mul(mem::size_of::<f16>())
+            .expect("`UntypedBytes::extend_f16_from_f32` length overflow");
+        self.reserve(size);
+        let mut halves = [f16::ZERO; CHUNK];
+        for chunk in values.chunks(CHUNK) {
+            let halves = &mut halves[..chunk.len()];
+            halves.convert_from_f32_slice(chunk);
+            self.extend_from_slice(&*halves);
+        }
+    }
+
+    /// Decodes the native-endian `f16`s in `byte_range` to `f32`s, which is exact. Panics if the
+    /// range is out of bounds or its length is not a multiple of 2.
+    pub fn extract_f32_from_f16<R: RangeBounds<usize>>(&self, byte_range: R) -> Vec<f32> {
+        let range = view::resolve(byte_range, self.len());
+        let size = mem::size_of::<f16>();
+        assert!(
+            range.len().is_multiple_of(size),
+            "`extract_f32_from_f16` range of {} bytes is not a multiple of {}",
+            range.len(),
+            size
+        );
+        let mut result = Vec::with_capacity(range.len() / size);
+        let mut halves = [f16::ZERO; CHUNK];
+        let mut floats = [0.0; CHUNK];
+        for chunk in self.bytes[range].chunks(CHUNK * size) {
+            let count = chunk.len() / size;
+            for (half, bytes) in halves.iter_mut().zip(chunk.chunks_exact(size)) {
+                *half = f16::from_ne_bytes([bytes[0], bytes[1]]);
+            }
+            halves[..count].convert_to_f32_slice(&mut floats[..count]);
+            result.extend_from_slice(&floats[..count]);
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bits(bytes: &UntypedBytes) -> Vec<u16> {
+        let view = bytes.as_view();
+        (0..bytes.len() / 2)
+            .map(|i| unsafe { view.read_at::<u16>(i * 2) })
+            .collect()
+    }
+
+    #[test]
+    fn known_bit_patterns() {
+        let values = [1.0, -2.0, 0.5, 0.0, -0.0, 65504.0, 5.960_464_5e-8];
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_f16_from_f32(&values);
+        assert_eq!(
+            bits(&bytes),
+            [0x3c00, 0xc000, 0x3800, 0x0000, 0x8000, 0x7bff, 0x0001]
+        );
+    }
+
+    #[test]
+    fn out_of_range_saturates_to_infinity() {
+        let mut bytes = UntypedBytes::new();
+        for &value in &[1.0e6, -1.0e6, f32::INFINITY, f32::NEG_INFINITY] {
+            bytes.push_f16(value);
+        }
+        assert_eq!(bits(&bytes), [0x7c00, 0xfc00, 0x7c00, 0xfc00]);
+        bytes.push_f16(f32::NAN);
+        assert!(bytes.extract_f32_from_f16(8..)[0].is_nan());
+    }
+
+    // Spans several chunks, with values that are exactly representable as `f16`.
+    #[test]
+    fn round_trip() {
+        let values: Vec<f32> = (0..200).map(|i| (i as f32 - 100.0) / 8.0).collect();
+        let mut bytes = UntypedBytes::from_slice([0xffu8; 2]);
+        bytes.extend_f16_from_f32(&values);
+        assert_eq!(bytes.len(), 402);
+        assert_eq!(bytes.extract_f32_from_f16(2..), values);
+        assert_eq!(bytes.extract_f32_from_f16(4..8), &values[1..3]);
+        assert!(bytes.extract_f32_from_f16(2..2).is_empty());
+
+        let mut pushed = UntypedBytes::from_slice([0xffu8; 2]);
+        for &value in &values {
+            pushed.push_f16(value);
+        }
+        assert_eq!(unsafe { pushed.as_slice() }, unsafe { bytes.as_slice() });
+    }
+
+    #[test]
+    #[should_panic(expected = "`extract_f32_from_f16` range of 3 bytes is not a multiple of 2")]
+    fn odd_range_panics() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_f16_from_f32(&[1.0, 2.0]);
+        bytes.extract_f32_from_f16(1..);
+    }
+}
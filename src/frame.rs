@@ -0,0 +1,255 @@
+// A framed on-disk format: a `Header` with `FRAME_MAGIC` and `FRAME_VERSION` whose `len` is the
+// payload length, a big-endian `u32` alignment hint, then the raw payload.
+
+use crate::{Header, HeaderError, UntypedBytes};
+use std::{
+    convert::{TryFrom, TryInto},
+    error::Error,
+    fmt,
+    io::{self, Read, Write},
+};
+
+const FRAME_MAGIC: u32 = u32::from_be_bytes(*b"UBYT");
+const FRAME_VERSION: u16 = 1;
+const FRAME_HEADER_SIZE: usize = Header::SIZE + 4;
+
+/// The error returned by `UntypedBytes::read_framed`.
+#[derive(Debug)]
+pub enum FrameError {
+    BadMagic {
+        expected: u32,
+        found: u32,
+    },
+    UnsupportedVersion {
+        found: u16,
+    },
+    /// The stream ended before the header or the full payload was read.
+    Truncated {
+        expected: u64,
+        found: u64,
+    },
+    Io(io::Error),
+}
+
+impl fmt::Display for FrameError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FrameError::BadMagic { expected, found } => write!(
+                f,
+                "bad frame magic: expected {:#010x}, found {:#010x}",
+                expected, found
+            ),
+            FrameError::UnsupportedVersion { found } => write!(
+                f,
+                "unsupported frame version {}, expected {}",
+                found, FRAME_VERSION
+            ),
+            FrameError::Truncated { expected, found } => write!(
+                f,
+                "frame is truncated: expected {} bytes, found {}",
+                expected, found
+            ),
+            FrameError::Io(err) => write!(f, "frame io error: {}", err),
+        }
+    }
+}
+
+impl Error for FrameError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            FrameError::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FrameError {
+    fn from(err: io::Error) -> Self {
+        FrameError::Io(err)
+    }
+}
+
+impl UntypedBytes {
+    /// Writes the bytes to `w` as a frame recording the payload length and `required_align`, the
+    /// alignment the payload needs when it is read back. Panics if `required_align` is not a
+    /// power of two that fits in a `u32`.
+    pub fn write_framed<W: Write>(&self, w: &mut W, required_align: usize) -> io::Result<()> {
+        assert!(
+            required_align.is_power_of_two(),
+            "alignment must be a power of two"
+        );
+        let align = u32::try_from(required_align).expect("alignment does not fit in a `u32`");
+        let mut header = UntypedBytes::with_capacity(FRAME_HEADER_SIZE);
+        Header {
+            magic: FRAME_MAGIC,
+            version: FRAME_VERSION,
+            flags: 0,
+            len: self.len() as u64,
+        }
+        .write(&mut header);
+        header.push(align.to_be_bytes());
+        w.write_all(&header.bytes)?;
+        w.write_all(&self.bytes)
+    }
+
+    /// Reads a frame written by `write_framed`, returning the payload and its alignment hint.
+    ///
+    /// The payload is read incrementally, so a corrupt length cannot cause a huge allocation up
+    /// front.
+    pub fn read_framed<R: Read>(r: &mut R) -> Result<(UntypedBytes, usize), FrameError> {
+        let mut header = [0; FRAME_HEADER_SIZE];
+        let found = read_fully(r, &mut header)?;
+        if found < FRAME_HEADER_SIZE {
+            return Err(FrameError::Truncated {
+                expected: FRAME_HEADER_SIZE as u64,
+                found: found as u64,
+            });
+        }
+        let parsed = Header::parse(&header, FRAME_MAGIC).map_err(|err| match err {
+            HeaderError::BadMagic { expected, found } => FrameError::BadMagic { expected, found },
+            HeaderError::Truncated { .. } => unreachable!(),
+        })?;
+        if parsed.version != FRAME_VERSION {
+            return Err(FrameError::UnsupportedVersion {
+                found: parsed.version,
+            });
+        }
+        let align = u32::from_be_bytes(header[Header::SIZE..].try_into().unwrap());
+
+        let mut bytes = Vec::new();
+        let found = r.take(parsed.len).read_to_end(&mut bytes)? as u64;
+        if found < parsed.len {
+            return Err(FrameError::Truncated {
+                expected: parsed.len,
+                found,
+            });
+        }
+        Ok((UntypedBytes::from_byte_vec(bytes), align as usize))
+    }
+}
+
+// Like `read_exact`, but returns how many bytes were read before the end of the stream.
+fn read_fully<R: Read>(r: &mut R, buf: &mut [u8]) -> io::Result<usize> {
+    let mut filled = 0;
+    while filled < buf.len() {
+        match r.read(&mut buf[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+            Err(err) => return Err(err),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn framed(bytes: &UntypedBytes, align: usize) -> Vec<u8> {
+        let mut out = Vec::new();
+        bytes.write_framed(&mut out, align).unwrap();
+        out
+    }
+
+    #[test]
+    fn round_trip() {
+        let bytes = UntypedBytes::from_slice([[1.0f32, 2.0, 3.0, 4.0]; 5]);
+        let out = framed(&bytes, 16);
+        assert_eq!(out.len(), FRAME_HEADER_SIZE + 80);
+        assert_eq!(&out[..4], b"UBYT");
+        let (read, align) = UntypedBytes::read_framed(&mut Cursor::new(&out)).unwrap();
+        assert_eq!(align, 16);
+        assert_eq!(read.bytes, bytes.bytes);
+
+        let (empty, align) =
+            UntypedBytes::read_framed(&mut &framed(&UntypedBytes::new(), 1)[..]).unwrap();
+        assert!(empty.is_empty());
+        assert_eq!(align, 1);
+    }
+
+    #[test]
+    fn back_to_back_frames() {
+        let mut out = framed(&UntypedBytes::from_slice([1u8, 2, 3]), 1);
+        out.extend(framed(&UntypedBytes::from_slice([4u64]), 8));
+        let mut r = Cursor::new(&out);
+        let (first, _) = UntypedBytes::read_framed(&mut r).unwrap();
+        let (second, align) = UntypedBytes::read_framed(&mut r).unwrap();
+        assert_eq!(first.bytes, [1, 2, 3]);
+        assert_eq!(second.bytes, 4u64.to_ne_bytes());
+        assert_eq!(align, 8);
+    }
+
+    #[test]
+    fn bad_magic() {
+        let mut out = framed(&UntypedBytes::from_slice([1u32]), 4);
+        out[0] = b'X';
+        match UntypedBytes::read_framed(&mut &out[..]) {
+            Err(FrameError::BadMagic { expected, found }) => {
+                assert_eq!(expected, FRAME_MAGIC);
+                assert_eq!(found, u32::from_be_bytes(*b"XBYT"));
+            }
+            other => panic!("expected bad magic, got {:?}", other),
+        }
+    }
+
+    #[test]
+    fn unsupported_version() {
+        let mut out = framed(&UntypedBytes::from_slice([1u32]), 4);
+        out[4..6].copy_from_slice(&2u16.to_be_bytes());
+        assert!(matches!(
+            UntypedBytes::read_framed(&mut &out[..]),
+            Err(FrameError::UnsupportedVersion { found: 2 })
+        ));
+    }
+
+    #[test]
+    fn truncated_header_and_payload() {
+        let out = framed(&UntypedBytes::from_slice([1u32, 2, 3]), 4);
+        assert!(matches!(
+            UntypedBytes::read_framed(&mut &out[..10]),
+            Err(FrameError::Truncated {
+                expected: 20,
+                found: 10
+            })
+        ));
+        assert!(matches!(
+            UntypedBytes::read_framed(&mut &out[..out.len() - 1]),
+            Err(FrameError::Truncated {
+                expected: 12,
+                found: 11
+            })
+        ));
+    }
+
+    // A corrupt length is only trusted as far as the stream goes.
+    #[test]
+    fn huge_length_is_truncated() {
+        let mut out = framed(&UntypedBytes::from_slice([1u32]), 4);
+        out[8..16].copy_from_slice(&u64::MAX.to_be_bytes());
+        assert!(matches!(
+            UntypedBytes::read_framed(&mut &out[..]),
+            Err(FrameError::Truncated {
+                expected: u64::MAX,
+                found: 4
+            })
+        ));
+    }
+
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("disk on fire"))
+        }
+    }
+
+    #[test]
+    fn io_error() {
+        let err = UntypedBytes::read_framed(&mut Failing).unwrap_err();
+        assert!(matches!(err, FrameError::Io(_)));
+        assert!(err.source().is_some());
+        assert_eq!(err.to_string(), "frame io error: disk on fire");
+    }
+}
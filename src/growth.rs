@@ -0,0 +1,83 @@
+/// How an `UntypedBytes` grows its capacity when a write does not fit, set with
+/// `UntypedBytes::with_growth`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum GrowthPolicy {
+    /// At least double the capacity, like `Vec`. Amortized O(1) pushes.
+    #[default]
+    Double,
+    /// Grow to the next multiple of the given number of bytes, bounding the unused capacity.
+    FixedChunk(usize),
+    /// Grow to exactly the required capacity.
+    Exact,
+}
+
+impl GrowthPolicy {
+    // The capacity to grow to from `capacity` when `required` bytes are needed.
+    pub(crate) fn grow(self, capacity: usize, required: usize) -> usize {
+        match self {
+            GrowthPolicy::Double => required.max(capacity.saturating_mul(2)).max(8),
+            GrowthPolicy::FixedChunk(chunk) => required
+                .checked_next_multiple_of(chunk)
+                .expect("capacity overflow"),
+            GrowthPolicy::Exact => required,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UntypedBytes;
+
+    #[test]
+    fn fixed_chunk_grows_in_steps() {
+        let mut bytes = UntypedBytes::with_growth(GrowthPolicy::FixedChunk(4096));
+        assert_eq!(bytes.capacity(), 0);
+        bytes.push(1u8);
+        assert_eq!(bytes.capacity(), 4096);
+        bytes.extend_from_slice([0u8; 4095]);
+        assert_eq!(bytes.capacity(), 4096);
+        bytes.push(2u8);
+        assert_eq!(bytes.capacity(), 8192);
+        bytes.extend_from_slice([0u8; 10_000]);
+        assert_eq!(bytes.capacity(), 16_384);
+        for _ in 0..1000 {
+            bytes.push([0u8; 7]);
+            assert!(bytes.capacity().is_multiple_of(4096));
+            assert!(bytes.capacity() - bytes.len() < 4096);
+        }
+    }
+
+    #[test]
+    fn exact_and_double() {
+        let mut exact = UntypedBytes::with_growth(GrowthPolicy::Exact);
+        for i in 1..=10u32 {
+            exact.push(i);
+            assert_eq!(exact.capacity(), 4 * i as usize);
+        }
+
+        let mut double = UntypedBytes::with_growth(GrowthPolicy::Double);
+        double.push(1u8);
+        assert_eq!(double.capacity(), 8);
+        double.extend_from_slice([0u8; 8]);
+        assert_eq!(double.capacity(), 16);
+        double.extend_from_slice([0u8; 100]);
+        assert_eq!(double.capacity(), 109);
+    }
+
+    #[test]
+    fn grow_math() {
+        assert_eq!(GrowthPolicy::FixedChunk(4096).grow(0, 1), 4096);
+        assert_eq!(GrowthPolicy::FixedChunk(4096).grow(4096, 4096), 4096);
+        assert_eq!(GrowthPolicy::FixedChunk(4096).grow(4096, 4097), 8192);
+        assert_eq!(GrowthPolicy::Double.grow(100, 101), 200);
+        assert_eq!(GrowthPolicy::Double.grow(0, 3), 8);
+        assert_eq!(GrowthPolicy::Exact.grow(100, 101), 101);
+    }
+
+    #[test]
+    #[should_panic(expected = "`GrowthPolicy::FixedChunk` requires a nonzero chunk size")]
+    fn zero_chunk_panics() {
+        UntypedBytes::with_growth(GrowthPolicy::FixedChunk(0));
+    }
+}
@@ -0,0 +1,129 @@
+use crate::UntypedBytes;
+use core::{
+    mem,
+    ops::{Deref, DerefMut},
+};
+
+/// A scoped writer created by `UntypedBytes::push_guard`, which truncates the buffer back to its
+/// starting length when dropped unless `commit` is called. If a writer panics partway through a
+/// record, the buffer is left without the torn record.
+///
+/// Guards can be nested, since each only remembers the length at which it was created.
+pub struct PushGuard<'a> {
+    bytes: &'a mut UntypedBytes,
+    start: usize,
+}
+
+impl UntypedBytes {
+    /// Returns a guard for speculative writes. See `PushGuard`.
+    pub fn push_guard(&mut self) -> PushGuard<'_> {
+        PushGuard {
+            start: self.len(),
+            bytes: self,
+        }
+    }
+}
+
+impl PushGuard<'_> {
+    /// Keeps everything written through the guard.
+    pub fn commit(self) {
+        mem::forget(self)
+    }
+}
+
+impl Deref for PushGuard<'_> {
+    type Target = UntypedBytes;
+
+    fn deref(&self) -> &UntypedBytes {
+        self.bytes
+    }
+}
+
+impl DerefMut for PushGuard<'_> {
+    fn deref_mut(&mut self) -> &mut UntypedBytes {
+        self.bytes
+    }
+}
+
+impl Drop for PushGuard<'_> {
+    fn drop(&mut self) {
+        self.bytes.truncate_bytes(self.start)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use std::panic::{self, AssertUnwindSafe};
+
+    fn frame() -> UntypedBytes {
+        let mut bytes = UntypedBytes::new();
+        bytes.push([1u32, 2]);
+        bytes
+    }
+
+    #[test]
+    fn panicking_writer_is_rolled_back() {
+        let mut bytes = frame();
+        let before = unsafe { bytes.as_slice() }.to_vec();
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut guard = bytes.push_guard();
+            guard.push(3u32);
+            guard.extend_from_slice([4u16, 5]);
+            panic!("invalid game state");
+        }));
+        assert!(result.is_err());
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(unsafe { bytes.as_slice() }, &before[..]);
+    }
+
+    #[test]
+    fn commit_keeps_writes() {
+        let mut bytes = frame();
+        let mut guard = bytes.push_guard();
+        guard.push(3u32);
+        guard.commit();
+        assert_eq!(bytes.len(), 12);
+        assert_eq!(unsafe { bytes.as_view().read_at::<u32>(8) }, 3);
+
+        // Dropping without committing discards the writes.
+        bytes.push_guard().push(4u32);
+        assert_eq!(bytes.len(), 12);
+    }
+
+    #[test]
+    fn nested_guards() {
+        let mut bytes = frame();
+        let mut outer = bytes.push_guard();
+        outer.push(3u32);
+        {
+            let mut inner = outer.push_guard();
+            inner.push(4u32);
+            inner.commit();
+        }
+        {
+            let mut inner = outer.push_guard();
+            inner.push(5u32);
+        }
+        assert_eq!(outer.len(), 16);
+        assert_eq!(unsafe { outer.as_view().read_at::<u32>(12) }, 4);
+        drop(outer);
+        assert_eq!(bytes.len(), 8);
+    }
+
+    #[test]
+    fn inner_panic_keeps_outer_writes() {
+        let mut bytes = frame();
+        let mut outer = bytes.push_guard();
+        outer.push(3u32);
+        let result = panic::catch_unwind(AssertUnwindSafe(|| {
+            let mut inner = outer.push_guard();
+            inner.push(4u32);
+            panic!("torn record");
+        }));
+        assert!(result.is_err());
+        assert_eq!(outer.len(), 12);
+        outer.commit();
+        assert_eq!(bytes.len(), 12);
+    }
+}
@@ -0,0 +1,144 @@
+use crate::UntypedBytes;
+use core::{convert::TryInto, fmt};
+
+/// A fixed 16 byte header for serialized formats: `[magic: u32, version: u16, flags: u16,
+/// len: u64]`, all big-endian.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct Header {
+    pub magic: u32,
+    pub version: u16,
+    pub flags: u16,
+    pub len: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HeaderError {
+    /// Fewer than `Header::SIZE` bytes were available.
+    Truncated {
+        len: usize,
+    },
+    BadMagic {
+        expected: u32,
+        found: u32,
+    },
+}
+
+impl fmt::Display for HeaderError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            HeaderError::Truncated { len } => write!(
+                f,
+                "header is truncated: expected {} bytes, found {}",
+                Header::SIZE,
+                len
+            ),
+            HeaderError::BadMagic { expected, found } => write!(
+                f,
+                "bad header magic: expected {:#010x}, found {:#010x}",
+                expected, found
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for HeaderError {}
+
+impl Header {
+    pub const SIZE: usize = 16;
+
+    pub fn write(&self, out: &mut UntypedBytes) {
+        out.push(self.magic.to_be_bytes());
+        out.push(self.version.to_be_bytes());
+        out.push(self.flags.to_be_bytes());
+        out.push(self.len.to_be_bytes());
+    }
+
+    /// Parses a header from the start of `bytes`, checking that it begins with `magic`.
+    pub fn parse(bytes: &[u8], magic: u32) -> Result<Self, HeaderError> {
+        let bytes: &[u8; Self::SIZE] = bytes
+            .get(..Self::SIZE)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(HeaderError::Truncated { len: bytes.len() })?;
+        let found = u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]);
+        if found != magic {
+            return Err(HeaderError::BadMagic {
+                expected: magic,
+                found,
+            });
+        }
+        Ok(Header {
+            magic,
+            version: u16::from_be_bytes([bytes[4], bytes[5]]),
+            flags: u16::from_be_bytes([bytes[6], bytes[7]]),
+            len: u64::from_be_bytes(bytes[8..].try_into().unwrap()),
+        })
+    }
+}
+
+impl UntypedBytes {
+    /// Parses a header from the start of the buffer. See `Header::parse`.
+    pub fn parse_header(&self, magic: u32) -> Result<Header, HeaderError> {
+        Header::parse(&self.bytes, magic)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAGIC: u32 = 0x5542_5954;
+
+    const HEADER: Header = Header {
+        magic: MAGIC,
+        version: 3,
+        flags: 0x8001,
+        len: 0x0102_0304_0506_0708,
+    };
+
+    #[test]
+    fn write_then_parse() {
+        let mut bytes = UntypedBytes::new();
+        HEADER.write(&mut bytes);
+        bytes.extend_from_slice(*b"payload");
+        assert_eq!(
+            &bytes.bytes[..Header::SIZE],
+            &[0x55, 0x42, 0x59, 0x54, 0, 3, 0x80, 1, 1, 2, 3, 4, 5, 6, 7, 8]
+        );
+        assert_eq!(bytes.parse_header(MAGIC), Ok(HEADER));
+        assert_eq!(Header::parse(&bytes.bytes, MAGIC), Ok(HEADER));
+    }
+
+    #[test]
+    fn rejects_wrong_magic() {
+        let mut bytes = UntypedBytes::new();
+        HEADER.write(&mut bytes);
+        let err = bytes.parse_header(0x1234_5678).unwrap_err();
+        assert_eq!(
+            err,
+            HeaderError::BadMagic {
+                expected: 0x1234_5678,
+                found: MAGIC
+            }
+        );
+        assert_eq!(
+            alloc::format!("{}", err),
+            "bad header magic: expected 0x12345678, found 0x55425954"
+        );
+    }
+
+    #[test]
+    fn rejects_truncated_header() {
+        let mut bytes = UntypedBytes::new();
+        HEADER.write(&mut bytes);
+        bytes.truncate_bytes(Header::SIZE - 1);
+        assert_eq!(
+            bytes.parse_header(MAGIC),
+            Err(HeaderError::Truncated { len: 15 })
+        );
+        assert_eq!(
+            Header::parse(&[], MAGIC),
+            Err(HeaderError::Truncated { len: 0 })
+        );
+    }
+}
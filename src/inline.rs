@@ -0,0 +1,215 @@
+use crate::{as_bytes, as_bytes_slice, PushBytes, UntypedBytes, UntypedSlice};
+use core::{fmt, mem, mem::MaybeUninit, ptr, slice};
+
+/// A fixed-capacity `UntypedBytes` stored inline in an array of `N` bytes, which never allocates.
+/// Pushing past the capacity panics, see `try_push` for a fallible alternative.
+#[derive(Clone, Copy)]
+pub struct InlineUntypedBytes<const N: usize> {
+    bytes: [MaybeUninit<u8>; N],
+    len: usize,
+}
+
+impl<const N: usize> Default for InlineUntypedBytes<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> fmt::Debug for InlineUntypedBytes<N> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("InlineUntypedBytes")
+            .field("bytes", &unsafe { self.as_slice() })
+            .finish()
+    }
+}
+
+impl<const N: usize> InlineUntypedBytes<N> {
+    pub const fn new() -> Self {
+        InlineUntypedBytes {
+            bytes: [MaybeUninit::uninit(); N],
+            len: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn capacity(&self) -> usize {
+        N
+    }
+
+    /// The number of bytes that can still be pushed.
+    pub fn remaining(&self) -> usize {
+        N - self.len
+    }
+
+    pub fn clear(&mut self) {
+        self.len = 0
+    }
+
+    /// Panics if there is not enough capacity for `value`.
+    pub fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        if self.try_push(value).is_err() {
+            self.overflow(mem::size_of::<T>())
+        }
+    }
+
+    /// Pushes `value` if there is enough capacity for it, otherwise returns it.
+    pub fn try_push<T: Copy + Send + Sync + 'static>(&mut self, value: T) -> Result<(), T> {
+        if mem::size_of::<T>() > self.remaining() {
+            return Err(value);
+        }
+        self.write_raw(unsafe { as_bytes(&value) });
+        Ok(())
+    }
+
+    /// Panics if there is not enough capacity for `values`.
+    pub fn extend_from_slice<T: Copy + Send + Sync + 'static>(&mut self, values: &[T]) {
+        let raw = unsafe { as_bytes_slice(values) };
+        if raw.len() > self.remaining() {
+            self.overflow(raw.len())
+        }
+        self.write_raw(raw)
+    }
+
+    fn write_raw(&mut self, raw: &[u8]) {
+        debug_assert!(raw.len() <= self.remaining());
+        unsafe {
+            ptr::copy_nonoverlapping(
+                raw.as_ptr(),
+                self.bytes.as_mut_ptr().add(self.len) as *mut u8,
+                raw.len(),
+            )
+        }
+        self.len += raw.len();
+    }
+
+    #[cold]
+    fn overflow(&self, size: usize) -> ! {
+        panic!(
+            "`InlineUntypedBytes` overflow: pushing {} bytes with {} remaining",
+            size,
+            self.remaining()
+        )
+    }
+
+    /// See `UntypedBytes::as_slice`.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        slice::from_raw_parts(self.bytes.as_ptr() as *const u8, self.len)
+    }
+
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        UntypedSlice::new(unsafe { self.as_slice() })
+    }
+
+    /// See `UntypedBytes::cast`.
+    pub unsafe fn cast<T: Copy + Send + Sync + 'static>(&self) -> T {
+        self.as_view().cast()
+    }
+
+    /// Copies the bytes into a new heap allocated buffer.
+    pub fn to_untyped_bytes(&self) -> UntypedBytes {
+        self.as_view().to_owned()
+    }
+}
+
+impl<const N: usize> PushBytes for InlineUntypedBytes<N> {
+    #[inline]
+    fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        InlineUntypedBytes::push(self, value)
+    }
+
+    #[inline]
+    fn extend_from_slice<T: Copy + Send + Sync + 'static>(&mut self, values: &[T]) {
+        InlineUntypedBytes::extend_from_slice(self, values)
+    }
+
+    #[inline]
+    fn written(&self) -> usize {
+        self.len
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // An encoder generic over the sink, as with any `PushBytes`.
+    fn encode_header(sink: &mut impl PushBytes) {
+        sink.push(0xfeed_u16);
+        sink.extend_from_slice(&[1u8, 2]);
+    }
+
+    #[test]
+    fn push_and_cast() {
+        let mut bytes = InlineUntypedBytes::<16>::new();
+        bytes.push([1u32, 2]);
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(bytes.remaining(), 8);
+        assert_eq!(unsafe { bytes.cast::<[u32; 2]>() }, [1, 2]);
+        bytes.clear();
+        assert!(bytes.is_empty());
+        assert_eq!(bytes.capacity(), 16);
+    }
+
+    #[test]
+    fn exactly_full() {
+        let mut bytes = InlineUntypedBytes::<12>::new();
+        bytes.push(1u64);
+        assert_eq!(bytes.try_push(2u32), Ok(()));
+        assert_eq!(bytes.remaining(), 0);
+        assert_eq!(bytes.try_push(3u8), Err(3));
+        assert_eq!(bytes.len(), 12);
+        // Zero-sized values still fit.
+        bytes.push(());
+
+        let mut bytes = InlineUntypedBytes::<6>::new();
+        bytes.extend_from_slice(&[1u16, 2, 3]);
+        assert_eq!(unsafe { bytes.as_slice() }.len(), 6);
+    }
+
+    #[test]
+    fn try_push_overflow_leaves_contents() {
+        let mut bytes = InlineUntypedBytes::<8>::new();
+        bytes.push(7u32);
+        assert_eq!(bytes.try_push(8u64), Err(8));
+        assert_eq!(bytes.len(), 4);
+        assert_eq!(unsafe { bytes.cast::<u32>() }, 7);
+
+        let mut empty = InlineUntypedBytes::<0>::new();
+        assert_eq!(empty.try_push(1u8), Err(1));
+    }
+
+    #[test]
+    #[should_panic(expected = "`InlineUntypedBytes` overflow: pushing 8 bytes with 4 remaining")]
+    fn push_overflow_panics() {
+        let mut bytes = InlineUntypedBytes::<8>::new();
+        bytes.push(7u32);
+        bytes.push(8u64);
+    }
+
+    #[test]
+    #[should_panic(expected = "`InlineUntypedBytes` overflow: pushing 6 bytes with 5 remaining")]
+    fn extend_overflow_panics() {
+        let mut bytes = InlineUntypedBytes::<5>::new();
+        bytes.extend_from_slice(&[1u16, 2, 3]);
+    }
+
+    #[test]
+    fn generic_sink() {
+        let mut inline = InlineUntypedBytes::<4>::new();
+        let mut heap = UntypedBytes::new();
+        encode_header(&mut inline);
+        encode_header(&mut heap);
+        assert_eq!(inline.written(), 4);
+        assert_eq!(unsafe { inline.as_slice() }, unsafe { heap.as_slice() });
+
+        let copied = inline.to_untyped_bytes();
+        assert_eq!(unsafe { copied.as_slice() }, unsafe { heap.as_slice() });
+    }
+}
@@ -0,0 +1,246 @@
+use crate::{fnv1a, UntypedBytes, UntypedSlice};
+use std::collections::HashMap;
+
+/// A handle to an entry in a `BytesInterner`. Byte-identical contents share one handle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct InternId {
+    index: usize,
+    offset: usize,
+    len: usize,
+}
+
+impl InternId {
+    /// The byte offset of the entry in the interner's arena, which never changes.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// The length of the entry in bytes.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Entry {
+    id: InternId,
+    hash: u64,
+    refs: usize,
+}
+
+/// Deduplicates byte-identical buffers, storing each unique one once in a single backing arena.
+///
+/// Entries are reference counted: every `intern` of the same contents adds a reference, and
+/// `release` drops one. Released entries are evicted from lookups, but their arena bytes are not
+/// reused until `clear`, so the offsets of live entries stay stable.
+#[derive(Clone, Debug, Default)]
+pub struct BytesInterner {
+    arena: UntypedBytes,
+    entries: Vec<Entry>,
+    // Content hash to the indices of live entries with that hash.
+    lookup: HashMap<u64, Vec<usize>>,
+    live: usize,
+}
+
+impl BytesInterner {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// The number of live unique entries.
+    pub fn len(&self) -> usize {
+        self.live
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.live == 0
+    }
+
+    /// Returns the id of the entry with the same contents as `bytes`, adding a reference to it,
+    /// or copies `bytes` into the arena as a new entry.
+    pub fn intern<'a>(&mut self, bytes: impl Into<UntypedSlice<'a>>) -> InternId {
+        let bytes = bytes.into();
+        let hash = fnv1a(unsafe { bytes.as_slice() });
+        let candidates = self.lookup.entry(hash).or_default();
+        for &index in candidates.iter() {
+            let entry = &mut self.entries[index];
+            let id = entry.id;
+            if unsafe { self.arena.as_slice()[id.offset..][..id.len] == *bytes.as_slice() } {
+                entry.refs += 1;
+                return id;
+            }
+        }
+        let id = InternId {
+            index: self.entries.len(),
+            offset: self.arena.len(),
+            len: bytes.len(),
+        };
+        self.arena.extend_from_view(bytes);
+        candidates.push(id.index);
+        self.entries.push(Entry { id, hash, refs: 1 });
+        self.live += 1;
+        id
+    }
+
+    /// Drops one reference to the entry, evicting it when none are left. Returns `true` if the
+    /// entry was evicted. Panics if the entry was already evicted.
+    pub fn release(&mut self, id: InternId) -> bool {
+        let entry = self.live_entry_mut(id);
+        entry.refs -= 1;
+        if entry.refs > 0 {
+            return false;
+        }
+        let hash = entry.hash;
+        let candidates = self.lookup.get_mut(&hash).unwrap();
+        candidates.retain(|&index| index != id.index);
+        if candidates.is_empty() {
+            self.lookup.remove(&hash);
+        }
+        self.live -= 1;
+        true
+    }
+
+    /// Returns the contents of the entry. Panics if the entry was evicted.
+    pub fn get(&self, id: InternId) -> UntypedSlice<'_> {
+        assert!(
+            self.entries[id.index].refs > 0,
+            "`InternId` refers to an evicted entry"
+        );
+        self.arena.view_range(id.offset..id.offset + id.len)
+    }
+
+    /// Copies the contents of the entry into a new buffer. Panics if the entry was evicted.
+    pub fn resolve_owned(&self, id: InternId) -> UntypedBytes {
+        self.get(id).to_owned()
+    }
+
+    /// Returns an iterator over the live entries in the order they were first interned.
+    pub fn iter(&self) -> impl Iterator<Item = (InternId, UntypedSlice<'_>)> + '_ {
+        self.entries
+            .iter()
+            .filter(|entry| entry.refs > 0)
+            .map(move |entry| (entry.id, self.get(entry.id)))
+    }
+
+    /// The backing arena holding every entry at its `InternId::offset`, for uploading in one
+    /// batch. Evicted entries leave stale bytes behind.
+    pub fn arena(&self) -> UntypedSlice<'_> {
+        self.arena.as_view()
+    }
+
+    /// Removes every entry, invalidating all ids, and reuses the arena from the start.
+    pub fn clear(&mut self) {
+        self.arena.clear();
+        self.entries.clear();
+        self.lookup.clear();
+        self.live = 0;
+    }
+
+    fn live_entry_mut(&mut self, id: InternId) -> &mut Entry {
+        let entry = &mut self.entries[id.index];
+        assert!(entry.refs > 0, "`InternId` refers to an evicted entry");
+        entry
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(values: [f32; 4]) -> UntypedBytes {
+        UntypedBytes::from_slice([values])
+    }
+
+    #[test]
+    fn identical_contents_share_an_id() {
+        let mut interner = BytesInterner::new();
+        let a = interner.intern(&block([1.0, 0.0, 0.0, 1.0]));
+        let b = interner.intern(&block([1.0, 0.0, 0.0, 1.0]));
+        let c = interner.intern(&block([0.0, 1.0, 0.0, 1.0]));
+        assert_eq!(a, b);
+        assert_ne!(a, c);
+        assert_eq!(interner.len(), 2);
+        assert_eq!(interner.arena().len(), 32);
+        assert_eq!(
+            unsafe { interner.get(c).cast::<[f32; 4]>() },
+            [0.0, 1.0, 0.0, 1.0]
+        );
+        assert_eq!(
+            unsafe { interner.resolve_owned(a).cast::<[f32; 4]>() },
+            [1.0, 0.0, 0.0, 1.0]
+        );
+    }
+
+    // Prefixes of one another are different contents.
+    #[test]
+    fn different_lengths_do_not_match() {
+        let mut interner = BytesInterner::new();
+        let short = interner.intern(&b"ab"[..]);
+        let long = interner.intern(&b"abc"[..]);
+        let empty = interner.intern(&b""[..]);
+        assert_ne!(short, long);
+        assert!(empty.is_empty());
+        assert_eq!(interner.intern(&b""[..]), empty);
+        assert_eq!((short.len(), long.len()), (2, 3));
+        assert_eq!(interner.len(), 3);
+    }
+
+    #[test]
+    fn offsets_are_stable() {
+        let mut interner = BytesInterner::new();
+        let first = interner.intern(&block([1.0; 4]));
+        let second = interner.intern(&block([2.0; 4]));
+        assert_eq!((first.offset(), second.offset()), (0, 16));
+        for i in 0..100 {
+            interner.intern(&block([i as f32 + 10.0; 4]));
+        }
+        assert_eq!(interner.intern(&block([1.0; 4])).offset(), 0);
+        assert_eq!(interner.intern(&block([2.0; 4])).offset(), 16);
+        assert_eq!(unsafe { interner.get(second).cast::<[f32; 4]>() }, [2.0; 4]);
+        let arena = unsafe { interner.arena().as_slice() };
+        assert_eq!(&arena[16..32], unsafe { interner.get(second).as_slice() });
+    }
+
+    #[test]
+    fn release_counts_references() {
+        let mut interner = BytesInterner::new();
+        let a = interner.intern(&b"shared"[..]);
+        interner.intern(&b"shared"[..]);
+        let b = interner.intern(&b"other"[..]);
+        assert!(!interner.release(a));
+        assert_eq!(interner.len(), 2);
+        assert!(interner.release(a));
+        assert_eq!(interner.len(), 1);
+        let live: Vec<InternId> = interner.iter().map(|(id, _)| id).collect();
+        assert_eq!(live, [b]);
+
+        // Interning the same contents again makes a new entry past the evicted one.
+        let again = interner.intern(&b"shared"[..]);
+        assert_ne!(again, a);
+        assert_eq!(again.offset(), 11);
+        assert_eq!(unsafe { interner.get(again).as_slice() }, b"shared");
+    }
+
+    #[test]
+    #[should_panic(expected = "`InternId` refers to an evicted entry")]
+    fn get_evicted_panics() {
+        let mut interner = BytesInterner::new();
+        let id = interner.intern(&b"gone"[..]);
+        interner.release(id);
+        interner.get(id);
+    }
+
+    #[test]
+    fn clear_starts_over() {
+        let mut interner = BytesInterner::new();
+        interner.intern(&b"abc"[..]);
+        interner.clear();
+        assert!(interner.is_empty());
+        assert_eq!(interner.arena().len(), 0);
+        assert_eq!(interner.intern(&b"xyz"[..]).offset(), 0);
+    }
+}
@@ -0,0 +1,126 @@
+use alloc::{string::String, vec::Vec};
+use core::{fmt::Write, ops::Range};
+
+const UNLABELED: &str = "<unlabeled>";
+
+// Side table of labeled writes, sorted by offset and never overlapping.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct Labels {
+    regions: Vec<(&'static str, Range<usize>)>,
+}
+
+impl Labels {
+    pub(crate) fn record(&mut self, label: &'static str, range: Range<usize>) {
+        if !range.is_empty() {
+            self.regions.push((label, range))
+        }
+    }
+
+    // Forgets or clips any regions past `len`.
+    pub(crate) fn truncate(&mut self, len: usize) {
+        self.regions.retain(|(_, range)| range.start < len);
+        if let Some((_, range)) = self.regions.last_mut() {
+            range.end = range.end.min(len);
+        }
+    }
+
+    pub(crate) fn region_at(
+        &self,
+        offset: usize,
+        len: usize,
+    ) -> Option<(&'static str, Range<usize>)> {
+        if offset >= len {
+            return None;
+        }
+        let next = self
+            .regions
+            .partition_point(|(_, range)| range.start <= offset);
+        if let Some((label, range)) = next.checked_sub(1).map(|i| &self.regions[i]) {
+            if offset < range.end {
+                return Some((label, range.start..range.end.min(len)));
+            }
+        }
+        let start = next.checked_sub(1).map_or(0, |i| self.regions[i].1.end);
+        let end = self
+            .regions
+            .get(next)
+            .map_or(len, |(_, range)| range.start.min(len));
+        Some((UNLABELED, start..end))
+    }
+
+    pub(crate) fn dump(&self, len: usize) -> String {
+        let mut result = String::new();
+        let mut offset = 0;
+        while let Some((label, range)) = self.region_at(offset, len) {
+            let _ = writeln!(
+                result,
+                "{:#010x}..{:#010x} ({} bytes) {}",
+                range.start,
+                range.end,
+                range.len(),
+                label
+            );
+            offset = range.end;
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UntypedBytes;
+
+    fn frame_constants() -> UntypedBytes {
+        let mut bytes = UntypedBytes::new();
+        bytes.push_labeled("view", [0f32; 4]);
+        bytes.push(0u32);
+        bytes.extend_from_slice_labeled("lights", [[1f32; 2]; 3]);
+        bytes
+    }
+
+    #[test]
+    fn region_at_middle_and_boundaries() {
+        let bytes = frame_constants();
+        assert_eq!(bytes.region_at(0), Some(("view", 0..16)));
+        assert_eq!(bytes.region_at(7), Some(("view", 0..16)));
+        assert_eq!(bytes.region_at(15), Some(("view", 0..16)));
+        assert_eq!(bytes.region_at(16), Some((UNLABELED, 16..20)));
+        assert_eq!(bytes.region_at(19), Some((UNLABELED, 16..20)));
+        assert_eq!(bytes.region_at(20), Some(("lights", 20..44)));
+        assert_eq!(bytes.region_at(43), Some(("lights", 20..44)));
+        assert_eq!(bytes.region_at(44), None);
+    }
+
+    #[test]
+    fn unlabeled_prefix_and_suffix() {
+        let mut bytes = UntypedBytes::from_slice([0u8; 2]);
+        bytes.push_labeled("id", 1u16);
+        bytes.push(0u8);
+        assert_eq!(bytes.region_at(0), Some((UNLABELED, 0..2)));
+        assert_eq!(bytes.region_at(3), Some(("id", 2..4)));
+        assert_eq!(bytes.region_at(4), Some((UNLABELED, 4..5)));
+    }
+
+    #[test]
+    fn dump_covers_every_byte() {
+        assert_eq!(
+            frame_constants().dump_regions(),
+            "0x00000000..0x00000010 (16 bytes) view\n\
+             0x00000010..0x00000014 (4 bytes) <unlabeled>\n\
+             0x00000014..0x0000002c (24 bytes) lights\n"
+        );
+        assert_eq!(UntypedBytes::new().dump_regions(), "");
+    }
+
+    #[test]
+    fn truncation_clips_regions() {
+        let mut bytes = frame_constants();
+        bytes.clear();
+        assert_eq!(bytes.region_at(0), None);
+        bytes.push(0u64);
+        assert_eq!(bytes.region_at(0), Some((UNLABELED, 0..8)));
+        bytes.push_labeled("tail", 0u8);
+        assert_eq!(bytes.region_at(8), Some(("tail", 8..9)));
+    }
+}
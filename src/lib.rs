@@ -1,12 +1,170 @@
-use std::{
-    borrow::Borrow,
+#![cfg_attr(not(feature = "std"), no_std)]
+#![allow(clippy::missing_safety_doc)]
+
+extern crate alloc;
+
+#[cfg(feature = "rkyv")]
+mod archive;
+#[cfg(feature = "tokio")]
+mod async_io;
+mod bits;
+#[cfg(feature = "bumpalo")]
+mod bump;
+mod compare;
+mod concurrent;
+mod cow;
+#[cfg(feature = "ct")]
+mod ct;
+#[cfg(feature = "digest")]
+mod digests;
+mod element;
+mod encode;
+mod error;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "flate2")]
+mod flate;
+#[cfg(feature = "half")]
+mod float16;
+#[cfg(feature = "std")]
+mod frame;
+mod growth;
+mod guard;
+mod header;
+mod inline;
+#[cfg(feature = "std")]
+mod interner;
+#[cfg(feature = "debug-labels")]
+mod labels;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod packing;
+#[cfg(feature = "rayon")]
+mod parallel;
+#[cfg(feature = "safe")]
+mod pod;
+#[cfg(feature = "std")]
+mod pool;
+mod push_bytes;
+mod push_fields;
+mod push_tuple;
+#[cfg(feature = "python")]
+mod python;
+mod queue;
+mod reset;
+mod search;
+mod shared;
+#[cfg(feature = "simd")]
+mod simd;
+mod static_bytes;
+#[cfg(all(feature = "debug-typecheck", debug_assertions))]
+mod typecheck;
+mod typed;
+mod view;
+#[cfg(feature = "wasm")]
+mod wasm;
+#[cfg(feature = "compress")]
+mod zstd;
+
+#[cfg(feature = "rkyv")]
+pub use archive::ArchivedUntypedBytes;
+pub use bits::{BitReader, BitWriter};
+#[cfg(feature = "bumpalo")]
+pub use bump::BumpBytes;
+pub use compare::ElementDiff;
+pub use concurrent::{ConcurrentUntypedBytes, WriteRegion};
+pub use cow::UntypedCow;
+pub use element::Element;
+pub use error::{CastError, IndexOutOfRange, PrefixedStrError, StrideError};
+#[cfg(feature = "std")]
+pub use frame::FrameError;
+pub use growth::GrowthPolicy;
+pub use guard::PushGuard;
+pub use header::{Header, HeaderError};
+pub use inline::InlineUntypedBytes;
+#[cfg(feature = "std")]
+pub use interner::{BytesInterner, InternId};
+#[cfg(feature = "mmap")]
+pub use mmap::MappedBytes;
+#[cfg(feature = "safe")]
+pub use pod::PodBytes;
+#[cfg(feature = "std")]
+pub use pool::{BytesPool, PooledBytes};
+pub use push_bytes::{FixedWriter, PushBytes};
+pub use push_fields::PushFields;
+pub use push_tuple::PushTuple;
+#[cfg(feature = "python")]
+pub use python::PyUntypedBytes;
+pub use queue::UntypedQueue;
+pub use reset::ResetPolicy;
+pub use shared::SharedUntypedBytes;
+pub use static_bytes::StaticUntypedBytes;
+pub use typed::{IntoIterAs, TypedViewMut};
+#[cfg(feature = "derive")]
+pub use untyped_bytes_derive::{Element, PushFields};
+pub use view::UntypedSlice;
+#[cfg(feature = "compress")]
+pub use zstd::DecompressError;
+
+use alloc::{borrow::Borrow, sync::Arc, vec, vec::Vec};
+use core::{
+    convert::TryInto,
+    iter::{self, FromIterator},
     mem::{self, MaybeUninit},
-    slice,
+    ops::RangeBounds,
+    ptr, slice,
+    sync::atomic,
 };
 
-#[derive(Clone, Debug, Default)]
+#[derive(Debug, Default)]
 pub struct UntypedBytes {
     bytes: Vec<u8>,
+    growth: GrowthPolicy,
+    reset: reset::ResetState,
+    #[cfg(feature = "debug-labels")]
+    labels: labels::Labels,
+    #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+    typecheck: typecheck::TypeCheck,
+}
+
+impl Clone for UntypedBytes {
+    fn clone(&self) -> Self {
+        Self {
+            bytes: self.bytes.clone(),
+            growth: self.growth,
+            reset: self.reset,
+            #[cfg(feature = "debug-labels")]
+            labels: self.labels.clone(),
+            #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+            typecheck: self.typecheck,
+        }
+    }
+
+    // Reuses the existing allocation when it is large enough.
+    fn clone_from(&mut self, source: &Self) {
+        self.bytes.clear();
+        self.bytes.extend_from_slice(&source.bytes);
+        self.growth = source.growth;
+        self.reset = source.reset;
+        #[cfg(feature = "debug-labels")]
+        self.labels.clone_from(&source.labels);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.clone_from(&source.typecheck);
+    }
+}
+
+/// Rounds `offset` up to the nearest multiple of `align`, which must be a power of two.
+#[inline]
+pub fn align_up(offset: usize, align: usize) -> usize {
+    debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
+    (offset + align - 1) & !(align - 1)
+}
+
+/// Rounds `offset` down to the nearest multiple of `align`, which must be a power of two.
+#[inline]
+pub fn align_down(offset: usize, align: usize) -> usize {
+    debug_assert!(align.is_power_of_two(), "alignment must be a power of two");
+    offset & !(align - 1)
 }
 
 // unsafe to inspect the bytes after casting
@@ -21,15 +179,70 @@ unsafe fn as_bytes_slice<T: Copy + Send + Sync + 'static>(value: &[T]) -> &[u8]
     slice::from_raw_parts(value.as_ptr() as _, mem::size_of_val(value))
 }
 
+// Reverses the order of the `size` byte blocks in `bytes`, whose length must be a multiple of
+// `size`.
+pub(crate) fn reverse_blocks(bytes: &mut [u8], size: usize) {
+    let count = bytes.len() / size;
+    for i in 0..count / 2 {
+        let (front, back) = bytes.split_at_mut((count - 1 - i) * size);
+        front[i * size..][..size].swap_with_slice(&mut back[..size]);
+    }
+}
+
+// Commits the written prefix of the spare capacity on drop, including when unwinding out of the
+// code producing the values.
+struct SetLenOnDrop<'a> {
+    bytes: &'a mut Vec<u8>,
+    len: usize,
+}
+
+impl Drop for SetLenOnDrop<'_> {
+    fn drop(&mut self) {
+        unsafe { self.bytes.set_len(self.len) }
+    }
+}
+
+// 64-bit FNV-1a.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    const OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+    const PRIME: u64 = 0x0100_0000_01b3;
+    bytes.iter().fold(OFFSET_BASIS, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(PRIME)
+    })
+}
+
 impl UntypedBytes {
     pub fn new() -> Self {
         Default::default()
     }
 
-    pub fn with_capacity(capacity: usize) -> Self {
+    fn from_byte_vec(bytes: Vec<u8>) -> Self {
         Self {
-            bytes: Vec::with_capacity(capacity),
+            bytes,
+            growth: Default::default(),
+            reset: Default::default(),
+            #[cfg(feature = "debug-labels")]
+            labels: Default::default(),
+            #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+            typecheck: Default::default(),
+        }
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self::from_byte_vec(Vec::with_capacity(capacity))
+    }
+
+    /// Creates an empty buffer that grows according to `policy`.
+    pub fn with_growth(policy: GrowthPolicy) -> Self {
+        if let GrowthPolicy::FixedChunk(chunk) = policy {
+            assert_ne!(
+                chunk, 0,
+                "`GrowthPolicy::FixedChunk` requires a nonzero chunk size"
+            );
         }
+        let mut result = Self::new();
+        result.growth = policy;
+        result
     }
 
     /// Effectively a `mem::transmute`.
@@ -43,7 +256,7 @@ impl UntypedBytes {
             )
         };
         mem::forget(value);
-        Self { bytes }
+        Self::from_byte_vec(bytes)
     }
 
     pub fn from_slice<T, V>(value: V) -> Self
@@ -55,9 +268,30 @@ impl UntypedBytes {
         let mut result = Self::with_capacity(mem::size_of_val(borrowed));
         let raw = unsafe { as_bytes_slice(borrowed) };
         result.bytes.extend(raw);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        result.typecheck.record::<T>(0..result.len());
         result
     }
 
+    /// Returns the backing bytes repeated `count` times, allocating once and filling the result by
+    /// repeatedly doubling the already written prefix.
+    pub fn repeat(&self, count: usize) -> Self {
+        let total = self
+            .len()
+            .checked_mul(count)
+            .expect("`UntypedBytes::repeat` length overflow");
+        let mut bytes = Vec::with_capacity(total);
+        if total > 0 {
+            bytes.extend_from_slice(&self.bytes);
+            while bytes.len() <= total / 2 {
+                bytes.extend_from_within(..);
+            }
+            let remaining = total - bytes.len();
+            bytes.extend_from_within(..remaining);
+        }
+        Self::from_byte_vec(bytes)
+    }
+
     pub fn is_empty(&self) -> bool {
         self.bytes.is_empty()
     }
@@ -66,13 +300,331 @@ impl UntypedBytes {
         self.bytes.len()
     }
 
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    /// Reserves capacity for at least `additional` more bytes, growing according to the
+    /// `GrowthPolicy` if the spare capacity is too small.
+    pub fn reserve(&mut self, additional: usize) {
+        let required = self
+            .len()
+            .checked_add(additional)
+            .expect("capacity overflow");
+        if required > self.bytes.capacity() {
+            let target = self.growth.grow(self.bytes.capacity(), required);
+            self.bytes.reserve_exact(target - self.len());
+        }
+    }
+
     pub fn clear(&mut self) {
-        self.bytes.clear()
+        self.truncate_bytes(0)
+    }
+
+    // Shortens the buffer to `len` bytes, keeping any side tables in sync.
+    pub(crate) fn truncate_bytes(&mut self, len: usize) {
+        self.bytes.truncate(len);
+        #[cfg(feature = "debug-labels")]
+        self.labels.truncate(len);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.truncate(len);
+    }
+
+    /// Like `clear`, but first overwrites the old contents with zeros using volatile writes, so
+    /// the wipe is not optimized away. The capacity is kept.
+    pub fn clear_and_zero(&mut self) {
+        for byte in self.bytes.iter_mut() {
+            unsafe { ptr::write_volatile(byte, 0) }
+        }
+        atomic::compiler_fence(atomic::Ordering::SeqCst);
+        self.clear()
+    }
+
+    /// Returns `true` if the backing bytes begin with `needle`.
+    pub fn starts_with(&self, needle: &[u8]) -> bool {
+        self.bytes.starts_with(needle)
+    }
+
+    /// Returns `true` if the backing bytes end with `needle`.
+    pub fn ends_with(&self, needle: &[u8]) -> bool {
+        self.bytes.ends_with(needle)
+    }
+
+    /// Returns the offset of the first occurrence of `needle` in the backing bytes. An empty
+    /// `needle` is found at offset `0`.
+    pub fn find(&self, needle: &[u8]) -> Option<usize> {
+        search::find(&self.bytes, needle)
+    }
+
+    /// Returns the offset of the last occurrence of `needle` in the backing bytes. An empty
+    /// `needle` is found at the end, at offset `len()`.
+    pub fn rfind(&self, needle: &[u8]) -> Option<usize> {
+        search::rfind(&self.bytes, needle)
+    }
+
+    /// Returns an iterator over the offsets of the non-overlapping occurrences of `needle`, from
+    /// the front, with the same semantics as `str::match_indices`. An empty `needle` matches at
+    /// every offset from `0` to `len()`.
+    pub fn find_iter<'a>(&'a self, needle: &'a [u8]) -> impl Iterator<Item = usize> + 'a {
+        let mut position = Some(0);
+        iter::from_fn(move || {
+            let start = position?;
+            let found = self
+                .bytes
+                .get(start..)
+                .and_then(|rest| search::find(rest, needle));
+            match found {
+                Some(offset) => {
+                    let offset = start + offset;
+                    position = Some(offset + needle.len().max(1));
+                    Some(offset)
+                }
+                None => {
+                    position = None;
+                    None
+                }
+            }
+        })
+    }
+
+    /// Returns `true` if `needle` occurs anywhere in the backing bytes. Always `true` for an
+    /// empty `needle`.
+    pub fn contains(&self, needle: &[u8]) -> bool {
+        self.find(needle).is_some()
+    }
+
+    /// Returns an iterator over the byte segments separated by `delimiter`, with the same
+    /// semantics as `[u8]::split`.
+    pub fn split(&self, delimiter: u8) -> impl Iterator<Item = &[u8]> + '_ {
+        self.bytes.split(move |&byte| byte == delimiter)
+    }
+
+    /// Hashes the backing bytes with 64-bit FNV-1a, which gives the same value on every platform
+    /// for the same byte content. Note that bytes written by `push` are native-endian, so the
+    /// content itself may differ between platforms.
+    pub fn stable_hash(&self) -> u64 {
+        fnv1a(&self.bytes)
     }
 
     pub fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
         let raw = unsafe { as_bytes(&value) };
-        self.bytes.extend(raw)
+        self.reserve(raw.len());
+        self.bytes.extend_from_slice(raw);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck
+            .record::<T>(self.len() - raw.len()..self.len());
+    }
+
+    /// Appends `n` values generated by calling `f` for each one, reserving space for all of them
+    /// up front.
+    pub fn fill_with<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        n: usize,
+        mut f: impl FnMut() -> T,
+    ) {
+        let size = n
+            .checked_mul(mem::size_of::<T>())
+            .expect("`UntypedBytes::fill_with` length overflow");
+        self.reserve(size);
+        for _ in 0..n {
+            self.push(f())
+        }
+    }
+
+    /// Appends `count` values, where the value at index `i` is `f(i)`, writing each straight into
+    /// capacity reserved up front. If `f` panics, the values produced before the panic are kept.
+    pub fn extend_with<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        count: usize,
+        mut f: impl FnMut(usize) -> T,
+    ) {
+        let size = mem::size_of::<T>();
+        let total = count
+            .checked_mul(size)
+            .expect("`UntypedBytes::extend_with` length overflow");
+        self.reserve(total);
+
+        let len = self.len();
+        let mut guard = SetLenOnDrop {
+            bytes: &mut self.bytes,
+            len,
+        };
+        for i in 0..count {
+            let value = f(i);
+            unsafe {
+                let dst = guard.bytes.as_mut_ptr().add(guard.len) as *mut T;
+                ptr::write_unaligned(dst, value);
+            }
+            guard.len += size;
+        }
+        drop(guard);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.record::<T>(len..self.len());
+    }
+
+    /// Appends `count` tightly packed `T` records read from `base + offset + i * stride`, pulling
+    /// a single attribute out of interleaved data. The records need not be aligned.
+    ///
+    /// This is only safe if every record lies within a single readable allocation and holds a
+    /// valid `T`. See `extend_from_strided_slice` for a bounds-checked version.
+    pub unsafe fn extend_from_strided<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        base: *const u8,
+        count: usize,
+        stride: usize,
+        offset: usize,
+    ) {
+        let base = base.add(offset);
+        self.extend_with(count, |i| {
+            ptr::read_unaligned(base.add(i * stride) as *const T)
+        })
+    }
+
+    /// Like `extend_from_strided`, but reads the records out of `source`, returning an error
+    /// instead of appending anything if the last record would end past the end of `source`. This
+    /// is only safe if each record holds a valid `T`.
+    pub unsafe fn extend_from_strided_slice<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        source: &[u8],
+        count: usize,
+        stride: usize,
+        offset: usize,
+    ) -> Result<(), StrideError> {
+        if count == 0 {
+            return Ok(());
+        }
+        let required = (count - 1)
+            .checked_mul(stride)
+            .and_then(|extent| extent.checked_add(offset))
+            .and_then(|extent| extent.checked_add(mem::size_of::<T>()))
+            .ok_or(StrideError::Overflow)?;
+        if required > source.len() {
+            return Err(StrideError::OutOfBounds {
+                required,
+                len: source.len(),
+            });
+        }
+        self.extend_from_strided::<T>(source.as_ptr(), count, stride, offset);
+        Ok(())
+    }
+
+    /// Appends `source[index]` for each of `indices`, in order; a gather, as when expanding
+    /// indexed vertex data into a flat stream. Every index is checked before anything is
+    /// appended, so on error the buffer is left unchanged.
+    pub fn extend_from_indexed<T, I>(
+        &mut self,
+        source: &[T],
+        indices: &[I],
+    ) -> Result<(), IndexOutOfRange>
+    where
+        T: Copy + Send + Sync + 'static,
+        I: Copy + TryInto<usize>,
+    {
+        let to_index = |index: I| index.try_into().ok().filter(|&index| index < source.len());
+        if let Some(position) = indices.iter().position(|&index| to_index(index).is_none()) {
+            return Err(IndexOutOfRange {
+                position,
+                len: source.len(),
+            });
+        }
+        self.extend_with(indices.len(), |i| {
+            let index = to_index(indices[i]).unwrap();
+            unsafe { *source.get_unchecked(index) }
+        });
+        Ok(())
+    }
+
+    /// Appends `n` zero bytes, returning the offset of the first one. Useful for reserving a slot
+    /// to be filled in later with `update_at`.
+    pub fn reserve_and_zero(&mut self, n: usize) -> usize {
+        let offset = self.len();
+        self.reserve(n);
+        self.bytes.resize(offset + n, 0);
+        offset
+    }
+
+    /// Reserves `additional` bytes and returns them as uninitialized spare capacity. Call
+    /// `advance` afterwards to commit the bytes that were written.
+    pub fn as_uninit_mut(&mut self, additional: usize) -> &mut [MaybeUninit<u8>] {
+        self.reserve(additional);
+        &mut self.bytes.spare_capacity_mut()[..additional]
+    }
+
+    /// Grows the length by `n` bytes into the spare capacity. This is only safe if those bytes
+    /// have been initialized, e.g. through `as_uninit_mut`.
+    pub unsafe fn advance(&mut self, n: usize) {
+        assert!(
+            n <= self.bytes.capacity() - self.len(),
+            "`advance` past the end of the spare capacity"
+        );
+        self.bytes.set_len(self.len() + n)
+    }
+
+    /// Appends `fill` bytes until the length is a multiple of `block`, which need not be a power
+    /// of two.
+    pub fn pad_to_multiple_of(&mut self, block: usize, fill: u8) {
+        assert_ne!(block, 0, "block size must be non-zero");
+        let padding = (block - self.len() % block) % block;
+        self.reserve(padding);
+        self.bytes.resize(self.len() + padding, fill)
+    }
+
+    /// Like `push`, but with the `debug-labels` feature records `label` for the written bytes so
+    /// they can be found again with `region_at`. Without the feature this is just `push`.
+    #[inline]
+    pub fn push_labeled<T: Copy + Send + Sync + 'static>(&mut self, label: &'static str, value: T) {
+        #[cfg(feature = "debug-labels")]
+        let start = self.start_labeled_write();
+        self.push(value);
+        #[cfg(feature = "debug-labels")]
+        self.labels.record(label, start..self.len());
+        #[cfg(not(feature = "debug-labels"))]
+        let _ = label;
+    }
+
+    /// Like `extend_from_slice`, but labeled as with `push_labeled`.
+    #[inline]
+    pub fn extend_from_slice_labeled<T, V>(&mut self, label: &'static str, value: V)
+    where
+        T: Copy + Send + Sync + 'static,
+        V: Borrow<[T]>,
+    {
+        #[cfg(feature = "debug-labels")]
+        let start = self.start_labeled_write();
+        self.extend_from_slice(value);
+        #[cfg(feature = "debug-labels")]
+        self.labels.record(label, start..self.len());
+        #[cfg(not(feature = "debug-labels"))]
+        let _ = label;
+    }
+
+    #[cfg(feature = "debug-labels")]
+    fn start_labeled_write(&mut self) -> usize {
+        // Labels past the current length were left behind by bytes that no longer exist.
+        self.labels.truncate(self.len());
+        self.len()
+    }
+
+    /// Returns the label and byte range of the write that covers `byte_offset`. Bytes written
+    /// without a label are reported as `"<unlabeled>"` regions spanning the gaps between labels.
+    #[cfg(feature = "debug-labels")]
+    pub fn region_at(&self, byte_offset: usize) -> Option<(&'static str, core::ops::Range<usize>)> {
+        self.labels.region_at(byte_offset, self.len())
+    }
+
+    /// Returns a human readable map of every region in the buffer, one per line.
+    #[cfg(feature = "debug-labels")]
+    pub fn dump_regions(&self) -> alloc::string::String {
+        self.labels.dump(self.len())
+    }
+
+    /// Pushes every element of a tuple in order, reserving space for all of them up front.
+    /// Returns the offset of the first pushed byte.
+    pub fn push_all<T: PushTuple>(&mut self, values: T) -> usize {
+        let offset = self.len();
+        self.reserve(T::SIZE);
+        values.push_into(self);
+        offset
     }
 
     #[inline]
@@ -82,7 +634,63 @@ impl UntypedBytes {
         V: Borrow<[T]>,
     {
         let raw = unsafe { as_bytes_slice(value.borrow()) };
-        self.bytes.extend_from_slice(raw)
+        self.reserve(raw.len());
+        self.bytes.extend_from_slice(raw);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck
+            .record::<T>(self.len() - raw.len()..self.len());
+    }
+
+    /// Appends every slice in order, reserving space for all of them up front. Returns the offset
+    /// of the first appended byte.
+    pub fn extend_from_slices<T, V>(&mut self, slices: &[V]) -> usize
+    where
+        T: Copy + Send + Sync + 'static,
+        V: Borrow<[T]>,
+    {
+        let offset = self.len();
+        let total = slices
+            .iter()
+            .try_fold(0usize, |total, slice| {
+                total.checked_add(mem::size_of_val(slice.borrow()))
+            })
+            .expect("capacity overflow");
+        self.reserve(total);
+        for slice in slices {
+            self.extend_from_slice(slice.borrow())
+        }
+        offset
+    }
+
+    /// Appends a `rows` by `cols` matrix stored in row-major order, transposing it to
+    /// column-major order if `transpose` is set.
+    pub fn push_matrix<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        rows: usize,
+        cols: usize,
+        row_major: &[T],
+        transpose: bool,
+    ) {
+        assert_eq!(
+            rows * cols,
+            row_major.len(),
+            "matrix dimensions do not match the number of elements"
+        );
+        if transpose {
+            self.reserve(mem::size_of_val(row_major));
+            for col in 0..cols {
+                for row in 0..rows {
+                    self.push(row_major[row * cols + col])
+                }
+            }
+        } else {
+            self.extend_from_slice(row_major)
+        }
+    }
+
+    pub fn extend_from_view(&mut self, view: UntypedSlice<'_>) {
+        self.reserve(view.len());
+        self.bytes.extend_from_slice(unsafe { view.as_slice() })
     }
 
     /// Returns a slice that is unsafe to inspect in the presence of padding bytes, but is safe to
@@ -92,9 +700,129 @@ impl UntypedBytes {
         &self.bytes
     }
 
+    /// Returns a borrowed view of the backing bytes.
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        UntypedSlice::new(&self.bytes)
+    }
+
+    /// Returns a borrowed view of the given byte range. Panics if the range is out of bounds.
+    pub fn view_range<R: RangeBounds<usize>>(&self, range: R) -> UntypedSlice<'_> {
+        UntypedSlice::new(&self.bytes[view::bounds(range)])
+    }
+
+    /// Converts the finished buffer into a reference counted slice that can be shared across
+    /// threads and cloned without copying the bytes.
+    pub fn freeze(self) -> Arc<[u8]> {
+        Arc::from(self.bytes.into_boxed_slice())
+    }
+
+    /// Reads the `T` at `byte_offset`, passes it to `f`, and writes the result back, regardless
+    /// of alignment. This is only safe if those bytes were created from a value of type `T`.
+    /// Panics if the value is out of bounds.
+    pub unsafe fn update_at<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        byte_offset: usize,
+        f: impl FnOnce(&mut T),
+    ) {
+        let len = self.len();
+        self.try_update_at(byte_offset, f).unwrap_or_else(|| {
+            panic!(
+                "`update_at` out of bounds: offset {} with size {} and length {}",
+                byte_offset,
+                mem::size_of::<T>(),
+                len
+            )
+        })
+    }
+
+    /// Like `update_at`, but returns `None` instead of panicking if the value is out of bounds.
+    pub unsafe fn try_update_at<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        byte_offset: usize,
+        f: impl FnOnce(&mut T),
+    ) -> Option<()> {
+        let end = byte_offset.checked_add(mem::size_of::<T>())?;
+        let ptr = self.bytes.get_mut(byte_offset..end)?.as_mut_ptr() as *mut T;
+        let mut value = ptr::read_unaligned(ptr);
+        f(&mut value);
+        ptr::write_unaligned(ptr, value);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.record::<T>(byte_offset..end);
+        Some(())
+    }
+
+    /// Rotates the bytes in place so that the byte at `mid` becomes the first. Panics if `mid` is
+    /// greater than the length.
+    pub fn rotate_left(&mut self, mid: usize) {
+        self.bytes.rotate_left(mid)
+    }
+
+    /// Rotates the bytes in place so that the last `k` bytes move to the front. Panics if `k` is
+    /// greater than the length.
+    pub fn rotate_right(&mut self, k: usize) {
+        self.bytes.rotate_right(k)
+    }
+
+    /// Overwrites every byte with `byte`, keeping the length unchanged.
+    pub fn fill_bytes(&mut self, byte: u8) {
+        self.bytes.fill(byte)
+    }
+
+    /// Reverses the order of the `chunk_size` byte chunks in the buffer, leaving the bytes within
+    /// each chunk in order. Panics if `chunk_size` is zero or does not divide the length.
+    pub fn reverse_chunks(&mut self, chunk_size: usize) {
+        assert_ne!(
+            chunk_size, 0,
+            "`reverse_chunks` requires a nonzero chunk size"
+        );
+        assert!(
+            self.len().is_multiple_of(chunk_size),
+            "length {} is not a multiple of the chunk size {}",
+            self.len(),
+            chunk_size
+        );
+        reverse_blocks(&mut self.bytes, chunk_size)
+    }
+
+    /// Swaps the `len` bytes starting at `a` with the `len` bytes starting at `b`. Panics if the
+    /// regions overlap or are out of bounds.
+    pub fn swap_ranges(&mut self, a: usize, b: usize, len: usize) {
+        let (low, high) = if a <= b { (a, b) } else { (b, a) };
+        assert!(
+            low + len <= high || len == 0,
+            "`swap_ranges` regions overlap"
+        );
+        assert!(high + len <= self.len(), "`swap_ranges` out of bounds");
+        let (left, right) = self.bytes.split_at_mut(high);
+        left[low..low + len].swap_with_slice(&mut right[..len])
+    }
+
+    /// Overwrites the bytes starting at `offset` with `values`, without changing the length.
+    /// Panics if the written region is out of bounds.
+    pub unsafe fn write_slice_at<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        offset: usize,
+        values: &[T],
+    ) {
+        let raw = as_bytes_slice(values);
+        debug_assert!(
+            offset + raw.len() <= self.len(),
+            "`write_slice_at` out of bounds"
+        );
+        self.bytes[offset..offset + raw.len()].copy_from_slice(raw);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.record::<T>(offset..offset + raw.len());
+    }
+
     /// Casts the backing bytes to a value of type `T`. This is only safe the backing bytes were
     /// created from a value of type `T`.
+    ///
+    /// With the `debug-typecheck` feature in debug builds, this panics if the whole buffer was
+    /// last written by `from_slice`, `push`, `extend_from_slice`, `extend_with`, or an in-place
+    /// write such as `write_slice_at` or `update_at`, as some type other than `T`.
     pub unsafe fn cast<T: Copy + Send + Sync + 'static>(&self) -> T {
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.check::<T>(self.len(), "cast");
         debug_assert_eq!(
             mem::size_of::<T>(),
             self.len(),
@@ -106,6 +834,67 @@ impl UntypedBytes {
             .copy_to_nonoverlapping(result.as_mut_ptr() as *mut u8, mem::size_of::<T>());
         result.assume_init()
     }
+
+    /// Like `cast`, but returns an error instead of asserting if the sizes differ. This is only
+    /// safe if the backing bytes were created from a value of type `T`. Checked by the
+    /// `debug-typecheck` feature like `cast`.
+    pub unsafe fn try_cast<T: Copy + Send + Sync + 'static>(&self) -> Result<T, CastError> {
+        if self.len() != mem::size_of::<T>() {
+            return Err(CastError::SizeMismatch {
+                expected: mem::size_of::<T>(),
+                found: self.len(),
+            });
+        }
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.check::<T>(self.len(), "try_cast");
+        Ok(self.as_view().read_at(0))
+    }
+
+    /// Copies the backing bytes out into a `Vec<T>`, returning an error if the length is not a
+    /// multiple of the size of `T`. This is only safe if the backing bytes were created from
+    /// values of type `T`. Checked by the `debug-typecheck` feature like `cast`.
+    pub unsafe fn try_cast_vec<T: Copy + Send + Sync + 'static>(
+        &self,
+    ) -> Result<Vec<T>, CastError> {
+        let size = mem::size_of::<T>();
+        assert_ne!(size, 0, "elements must not be zero-sized");
+        if !self.len().is_multiple_of(size) {
+            return Err(CastError::NotAMultiple {
+                element_size: size,
+                found: self.len(),
+            });
+        }
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.check::<T>(self.len(), "try_cast_vec");
+        let count = self.len() / size;
+        let mut result = Vec::<T>::with_capacity(count);
+        self.bytes
+            .as_ptr()
+            .copy_to_nonoverlapping(result.as_mut_ptr() as *mut u8, self.len());
+        result.set_len(count);
+        Ok(result)
+    }
+
+    /// Borrows the backing bytes as a `&[T]` without copying, returning an error if the length is
+    /// not a multiple of the size of `T` or the bytes are not aligned for `T`. Use `try_cast_vec`
+    /// to copy out of unaligned bytes. This is only safe if the backing bytes were created from
+    /// values of type `T`. Checked by the `debug-typecheck` feature like `cast`.
+    pub unsafe fn try_cast_slice<T: Copy + Send + Sync + 'static>(
+        &self,
+    ) -> Result<&[T], CastError> {
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.check::<T>(self.len(), "try_cast_slice");
+        self.as_view().try_cast_slice()
+    }
+
+    /// Like `UntypedSlice::read_at`, but returns an error instead of panicking if the value is out
+    /// of bounds. This is only safe if those bytes were created from a value of type `T`.
+    pub unsafe fn try_read_at<T: Copy + Send + Sync + 'static>(
+        &self,
+        offset: usize,
+    ) -> Result<T, CastError> {
+        self.as_view().try_read_at(offset)
+    }
 }
 
 impl<T: Copy + Send + Sync + 'static> From<T> for UntypedBytes {
@@ -114,41 +903,818 @@ impl<T: Copy + Send + Sync + 'static> From<T> for UntypedBytes {
     }
 }
 
-impl<A: Copy + Send + Sync + 'static> Extend<A> for UntypedBytes {
+// Slice and `Vec` sources are better served by `extend_from_slice`, which is a single `memcpy`.
+impl<A: Element> Extend<A> for UntypedBytes {
+    /// Reserves space for the iterator's `size_hint`, then writes each element straight into the
+    /// spare capacity, growing again only if the iterator yields more than it promised.
     #[inline]
     fn extend<T: IntoIterator<Item = A>>(&mut self, value: T) {
-        if std::any::type_name::<T>()
-            == std::any::type_name::<std::iter::Copied<std::slice::Iter<'_, A>>>()
-        {
-            let raw = unsafe { mem::transmute_copy::<_, std::slice::Iter<'_, A>>(&value) };
-            self.extend_from_slice(raw.as_slice())
-        } else if std::any::type_name::<T>()
-            == std::any::type_name::<std::iter::Cloned<std::slice::Iter<'_, A>>>()
-        {
-            let raw = unsafe { mem::transmute_copy::<_, std::slice::Iter<'_, A>>(&value) };
-            self.extend_from_slice(raw.as_slice())
-        } else if std::any::type_name::<T>()
-            == std::any::type_name::<std::iter::Copied<std::slice::IterMut<'_, A>>>()
-        {
-            let raw = unsafe { mem::transmute_copy::<_, std::slice::IterMut<'_, A>>(&value) };
-            self.extend_from_slice(raw.into_slice())
-        } else if std::any::type_name::<T>()
-            == std::any::type_name::<std::iter::Cloned<std::slice::IterMut<'_, A>>>()
-        {
-            let raw = unsafe { mem::transmute_copy::<_, std::slice::IterMut<'_, A>>(&value) };
-            self.extend_from_slice(raw.into_slice())
-        } else if std::any::type_name::<T>() == std::any::type_name::<std::vec::IntoIter<A>>() {
-            let raw = unsafe { mem::transmute_copy::<_, std::vec::IntoIter<A>>(&value) };
-            std::mem::forget(value);
-            self.extend_from_slice(raw.as_slice())
-        } else if std::any::type_name::<T>() == std::any::type_name::<Vec<A>>() {
-            let raw = unsafe { mem::transmute_copy::<_, Vec<A>>(&value) };
-            std::mem::forget(value);
-            self.extend_from_slice(raw.as_slice())
-        } else {
-            for elem in value {
-                self.push(elem)
+        let size = mem::size_of::<A>();
+        let mut iter = value.into_iter();
+        if size == 0 {
+            iter.for_each(drop);
+            return;
+        }
+        loop {
+            let (lower, _) = iter.size_hint();
+            self.reserve(lower.saturating_add(1).saturating_mul(size));
+            let room = (self.bytes.capacity() - self.len()) / size;
+            let len = self.len();
+            let mut guard = SetLenOnDrop {
+                bytes: &mut self.bytes,
+                len,
+            };
+            let mut exhausted = false;
+            for _ in 0..room {
+                match iter.next() {
+                    Some(elem) => {
+                        unsafe {
+                            let dst = guard.bytes.as_mut_ptr().add(guard.len) as *mut A;
+                            ptr::write_unaligned(dst, elem);
+                        }
+                        guard.len += size;
+                    }
+                    None => {
+                        exhausted = true;
+                        break;
+                    }
+                }
+            }
+            drop(guard);
+            #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+            self.typecheck.record::<A>(len..self.len());
+            if exhausted {
+                return;
+            }
+        }
+    }
+}
+
+// Concatenates the buffers in order. The lengths are summed first and reserved in one go, so even
+// `GrowthPolicy::Exact` reallocates at most once.
+fn append_parts<'a, I>(bytes: &mut UntypedBytes, parts: I)
+where
+    I: Iterator<Item = &'a UntypedBytes> + Clone,
+{
+    let total = parts
+        .clone()
+        .try_fold(0usize, |total, part| total.checked_add(part.len()))
+        .expect("capacity overflow");
+    bytes.reserve(total);
+    for part in parts {
+        bytes.bytes.extend_from_slice(&part.bytes)
+    }
+}
+
+impl Extend<UntypedBytes> for UntypedBytes {
+    /// If `self` is empty, takes over the allocation of the first buffer when it is the larger of
+    /// the two, rather than copying it.
+    fn extend<T: IntoIterator<Item = UntypedBytes>>(&mut self, value: T) {
+        let mut parts: Vec<UntypedBytes> = value.into_iter().collect();
+        match parts.first_mut() {
+            Some(first) if self.is_empty() && first.capacity() > self.capacity() => {
+                self.bytes = mem::take(&mut first.bytes);
+                append_parts(self, parts[1..].iter())
             }
+            _ => append_parts(self, parts.iter()),
+        }
+    }
+}
+
+impl<'a> Extend<&'a UntypedBytes> for UntypedBytes {
+    /// Collects the references first, so the total length can be reserved before copying.
+    fn extend<T: IntoIterator<Item = &'a UntypedBytes>>(&mut self, value: T) {
+        let parts: Vec<&UntypedBytes> = value.into_iter().collect();
+        append_parts(self, parts.iter().copied())
+    }
+}
+
+impl FromIterator<UntypedBytes> for UntypedBytes {
+    fn from_iter<T: IntoIterator<Item = UntypedBytes>>(value: T) -> Self {
+        let mut result = Self::new();
+        result.extend(value);
+        result
+    }
+}
+
+impl<'a> FromIterator<&'a UntypedBytes> for UntypedBytes {
+    fn from_iter<T: IntoIterator<Item = &'a UntypedBytes>>(value: T) -> Self {
+        let mut result = Self::new();
+        result.extend(value);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Each `Extend` source kind goes through the same spare-capacity writes, so these are worth
+    // running under Miri as well as natively.
+    fn check_extend<I: IntoIterator<Item = u32>>(source: I) {
+        let mut bytes = UntypedBytes::from_slice([0xaau8]);
+        bytes.extend(source);
+        let mut expected = vec![0xaau8];
+        for value in [1u32, 2, 3, 0xdead_beef].iter() {
+            expected.extend_from_slice(&value.to_ne_bytes());
+        }
+        assert_eq!(unsafe { bytes.as_slice() }, expected.as_slice());
+    }
+
+    const VALUES: [u32; 4] = [1, 2, 3, 0xdead_beef];
+
+    #[test]
+    fn extend_from_vec() {
+        check_extend(VALUES.to_vec());
+    }
+
+    #[test]
+    fn extend_from_vec_into_iter() {
+        let iter: alloc::vec::IntoIter<u32> = VALUES.to_vec().into_iter();
+        check_extend(iter);
+    }
+
+    #[test]
+    fn extend_from_copied_slice_iter() {
+        check_extend(VALUES.iter().copied());
+    }
+
+    #[test]
+    fn extend_from_cloned_slice_iter() {
+        check_extend(VALUES.iter().cloned());
+    }
+
+    #[test]
+    fn extend_from_mapped_iter() {
+        check_extend((0..4).map(|i| VALUES[i]));
+    }
+
+    // Yields more elements than its `size_hint` promises, so the buffer has to grow mid-extend.
+    #[test]
+    fn extend_past_size_hint() {
+        check_extend(VALUES.iter().copied().filter(|_| true));
+    }
+
+    #[test]
+    fn extend_with_zero_sized_elements() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend((0..10).map(|_| ()));
+        assert!(bytes.is_empty());
+    }
+
+    fn repeat_naive(bytes: &UntypedBytes, count: usize) -> UntypedBytes {
+        let mut result = UntypedBytes::new();
+        for _ in 0..count {
+            result.extend_from_view(bytes.as_view());
+        }
+        result
+    }
+
+    #[test]
+    fn repeat_matches_naive_loop() {
+        let template = UntypedBytes::from_slice([1u16, 2, 3]);
+        for count in (0..20).chain([63, 64, 65, 1000].iter().copied()) {
+            let repeated = template.repeat(count);
+            assert_eq!(repeated.len(), 6 * count);
+            assert_eq!(repeated.capacity(), 6 * count);
+            assert_eq!(unsafe { repeated.as_slice() }, unsafe {
+                repeat_naive(&template, count).as_slice()
+            });
+        }
+        assert!(UntypedBytes::new().repeat(100).is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "`UntypedBytes::repeat` length overflow")]
+    fn repeat_panics_on_overflow() {
+        UntypedBytes::from_slice([0u32]).repeat(usize::MAX / 2);
+    }
+
+    #[test]
+    fn extend_with_matches_push_loop() {
+        let mut bytes = UntypedBytes::from_slice([0xffu8]);
+        bytes.extend_with(100, |i| (i as u16, i as u8));
+        let mut expected = UntypedBytes::from_slice([0xffu8]);
+        for i in 0..100 {
+            expected.push((i as u16, i as u8));
+        }
+        assert_eq!(unsafe { bytes.as_slice() }, unsafe { expected.as_slice() });
+
+        bytes.extend_with(0, |_| -> u64 { unreachable!() });
+        assert_eq!(bytes.len(), expected.len());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn extend_with_keeps_prefix_on_panic() {
+        let mut bytes = UntypedBytes::from_slice([7u32]);
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            bytes.extend_with(10, |i| {
+                assert!(i < 3, "stop");
+                i as u32 * 10
+            })
+        }));
+        assert!(result.is_err());
+        assert_eq!(unsafe { bytes.to_vec_of::<u32, _>(..) }, vec![7, 0, 10, 20]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`UntypedBytes::extend_with` length overflow")]
+    fn extend_with_panics_on_overflow() {
+        UntypedBytes::new().extend_with(usize::MAX, |_| 0u16);
+    }
+
+    fn parts() -> Vec<UntypedBytes> {
+        (0..4u8)
+            .map(|i| UntypedBytes::from_slice(vec![i; i as usize + 1]))
+            .collect()
+    }
+
+    const CONCATENATED: [u8; 10] = [0, 1, 1, 2, 2, 2, 3, 3, 3, 3];
+
+    #[test]
+    fn collect_from_map() {
+        let merged: UntypedBytes = (0..4u8)
+            .map(|i| UntypedBytes::from_slice(vec![i; i as usize + 1]))
+            .collect();
+        assert_eq!(unsafe { merged.as_slice() }, &CONCATENATED);
+
+        let parts = parts();
+        let merged: UntypedBytes = parts.iter().collect();
+        assert_eq!(unsafe { merged.as_slice() }, &CONCATENATED);
+    }
+
+    #[test]
+    fn extend_from_slice_of_buffers() {
+        let parts = parts();
+        let mut merged = UntypedBytes::from_slice([0xffu8]);
+        merged.extend(parts.iter());
+        merged.extend(&parts[3..]);
+        assert_eq!(
+            unsafe { &merged.as_slice()[..11] },
+            &[0xff, 0, 1, 1, 2, 2, 2, 3, 3, 3, 3]
+        );
+        assert_eq!(unsafe { &merged.as_slice()[11..] }, &[3; 4]);
+    }
+
+    // The parts land in iteration order, whether they're owned or borrowed, and whether or not
+    // the first one's allocation is taken over.
+    #[test]
+    fn concatenation_keeps_order() {
+        let mut reversed = parts();
+        reversed.reverse();
+        let expected: Vec<u8> = CONCATENATED.iter().rev().copied().collect();
+
+        let mut merged = UntypedBytes::new();
+        merged.extend(reversed.clone());
+        assert_eq!(unsafe { merged.as_slice() }, expected.as_slice());
+
+        let mut merged = UntypedBytes::with_capacity(64);
+        merged.extend(reversed.iter());
+        assert_eq!(unsafe { merged.as_slice() }, expected.as_slice());
+    }
+
+    #[test]
+    fn extend_takes_over_first_allocation() {
+        let mut first = UntypedBytes::with_capacity(256);
+        first.extend_from_slice([1u8, 2]);
+        let ptr = unsafe { first.as_slice() }.as_ptr();
+        let mut merged = UntypedBytes::new();
+        merged.extend(vec![first, UntypedBytes::from_slice([3u8])]);
+        assert_eq!(unsafe { merged.as_slice() }, &[1, 2, 3]);
+        assert_eq!(unsafe { merged.as_slice() }.as_ptr(), ptr);
+    }
+
+    // Growing part by part would double 100, 200, 400, 800, 1600, so a capacity of exactly the
+    // total shows it was reserved once.
+    #[test]
+    fn extend_reserves_total_once() {
+        let parts: Vec<UntypedBytes> = (0..10u8)
+            .map(|i| UntypedBytes::from_slice([i; 100]))
+            .collect();
+        let mut borrowed = UntypedBytes::new();
+        borrowed.extend(parts.iter());
+        assert_eq!(borrowed.len(), 1000);
+        assert_eq!(borrowed.capacity(), 1000);
+
+        // The first part's allocation is taken over and only the rest is reserved.
+        let owned: UntypedBytes = parts.into_iter().collect();
+        assert_eq!(owned.capacity(), 1000);
+        assert_eq!(unsafe { owned.as_slice() }, unsafe { borrowed.as_slice() });
+    }
+
+    // A `&'static UntypedBytes` is concatenated, not appended as a pointer.
+    #[test]
+    fn extend_with_static_buffer_concatenates() {
+        let part: &'static mut UntypedBytes =
+            alloc::boxed::Box::leak(alloc::boxed::Box::new(UntypedBytes::from_slice([3u8, 4])));
+        let mut bytes = UntypedBytes::from_slice([1u8, 2]);
+        bytes.extend(iter::once(&*part));
+        assert_eq!(unsafe { bytes.as_slice() }, &[1, 2, 3, 4]);
+        drop(unsafe { alloc::boxed::Box::from_raw(part) });
+    }
+
+    #[test]
+    fn detects_magic_number_and_trailer() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_from_slice(*b"RIFF");
+        bytes.push(0x1234u32);
+        bytes.extend_from_slice(*b"END");
+        assert!(bytes.starts_with(b"RIFF"));
+        assert!(!bytes.starts_with(b"RIFX"));
+        assert!(bytes.starts_with(b""));
+        assert!(bytes.ends_with(b"END"));
+        assert!(!bytes.ends_with(b"RIFF"));
+        assert!(!UntypedBytes::from_slice(*b"RIF").starts_with(b"RIFF"));
+    }
+
+    #[test]
+    fn split_on_nul() {
+        let bytes = UntypedBytes::from_slice(*b"ab\0\0cde\0");
+        let segments: Vec<&[u8]> = bytes.split(0).collect();
+        assert_eq!(segments, [&b"ab"[..], b"", b"cde", b""]);
+        assert_eq!(UntypedBytes::new().split(0).collect::<Vec<_>>(), [&b""[..]]);
+    }
+
+    #[test]
+    fn contains_present_absent_and_empty() {
+        let bytes = UntypedBytes::from_slice(*b"data\xde\xad\xbe\xefmore");
+        assert!(bytes.contains(&[0xde, 0xad, 0xbe, 0xef]));
+        assert!(bytes.contains(b"more"));
+        assert!(!bytes.contains(&[0xbe, 0xef, 0xde]));
+        assert!(bytes.contains(b""));
+        assert!(UntypedBytes::new().contains(b""));
+        assert!(!UntypedBytes::new().contains(b"d"));
+    }
+
+    #[test]
+    fn clone_from_reuses_allocation() {
+        let source = UntypedBytes::from_slice([1u32, 2, 3]);
+        let mut target = UntypedBytes::with_capacity(64);
+        target.push(9u64);
+        let ptr = unsafe { target.as_slice() }.as_ptr();
+        target.clone_from(&source);
+        assert_eq!(target.capacity(), 64);
+        assert_eq!(unsafe { target.as_slice() }.as_ptr(), ptr);
+        assert_eq!(unsafe { target.as_slice() }, unsafe { source.as_slice() });
+
+        let mut small = UntypedBytes::new();
+        small.clone_from(&source);
+        assert_eq!(unsafe { small.as_slice() }, unsafe { source.as_slice() });
+    }
+
+    #[test]
+    fn extend_from_slices_matches_sequential_extends() {
+        let skeletons: [&[[f32; 4]]; 4] = [&[[1.0; 4], [2.0; 4]], &[], &[[3.0; 4]], &[[4.0; 4]; 3]];
+        let mut batched = UntypedBytes::from_slice([0xffu8]);
+        assert_eq!(batched.extend_from_slices(&skeletons), 1);
+        let mut sequential = UntypedBytes::from_slice([0xffu8]);
+        for skeleton in skeletons.iter() {
+            sequential.extend_from_slice(*skeleton);
+        }
+        assert_eq!(unsafe { batched.as_slice() }, unsafe {
+            sequential.as_slice()
+        });
+    }
+
+    // Extending by these one at a time would grow the buffer from 8 to 16 and then 32 bytes.
+    #[test]
+    fn extend_from_slices_reserves_once() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push(0u8);
+        let slices = [vec![1u16; 5], vec![], vec![2u16; 3]];
+        assert_eq!(bytes.extend_from_slices(&slices), 1);
+        assert_eq!(bytes.len(), 17);
+        assert_eq!(bytes.capacity(), 17);
+
+        let empty: [&[u64]; 0] = [];
+        assert_eq!(bytes.extend_from_slices(&empty), 17);
+        assert_eq!(bytes.len(), 17);
+    }
+
+    #[test]
+    fn clear_and_zero_wipes_spare_capacity() {
+        let mut bytes = UntypedBytes::from_slice([0xdead_beefu32; 4]);
+        let capacity = bytes.capacity();
+        bytes.clear_and_zero();
+        assert!(bytes.is_empty());
+        assert_eq!(bytes.capacity(), capacity);
+        let spare = unsafe { slice::from_raw_parts(bytes.bytes.as_ptr(), 16) };
+        assert_eq!(spare, &[0; 16]);
+    }
+
+    #[test]
+    fn align_up_and_down() {
+        let cases = [
+            (0, 1, 0, 0),
+            (0, 16, 0, 0),
+            (1, 1, 1, 1),
+            (1, 4, 4, 0),
+            (4, 4, 4, 4),
+            (5, 4, 8, 4),
+            (17, 16, 32, 16),
+            (256, 256, 256, 256),
+        ];
+        for &(offset, align, up, down) in cases.iter() {
+            assert_eq!(
+                align_up(offset, align),
+                up,
+                "align_up({}, {})",
+                offset,
+                align
+            );
+            assert_eq!(
+                align_down(offset, align),
+                down,
+                "align_down({}, {})",
+                offset,
+                align
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "power of two")]
+    #[cfg(debug_assertions)]
+    fn align_up_rejects_non_power_of_two() {
+        align_up(5, 3);
+    }
+
+    #[test]
+    fn update_at_increments_header_field() {
+        let mut bytes = UntypedBytes::from_slice([0xaau8]);
+        bytes.push(41u32);
+        bytes.push(0xbbu8);
+        unsafe { bytes.update_at::<u32>(1, |count| *count += 1) };
+        assert_eq!(unsafe { bytes.as_view().read_at::<u32>(1) }, 42);
+        assert_eq!(unsafe { bytes.as_slice() }[0], 0xaa);
+        assert_eq!(unsafe { bytes.as_slice() }[5], 0xbb);
+    }
+
+    #[test]
+    fn try_update_at_out_of_bounds() {
+        let mut bytes = UntypedBytes::from_slice([0u8; 6]);
+        assert_eq!(
+            unsafe { bytes.try_update_at::<u32>(2, |v| *v = u32::MAX) },
+            Some(())
+        );
+        assert_eq!(
+            unsafe { bytes.try_update_at::<u32>(3, |_| unreachable!()) },
+            None
+        );
+        assert_eq!(
+            unsafe { bytes.try_update_at::<u8>(usize::MAX, |_| unreachable!()) },
+            None
+        );
+        assert_eq!(unsafe { bytes.as_slice() }, &[0, 0, 0xff, 0xff, 0xff, 0xff]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`update_at` out of bounds")]
+    fn update_at_panics_out_of_bounds() {
+        let mut bytes = UntypedBytes::from_slice([0u32]);
+        unsafe { bytes.update_at::<u16>(3, |_| ()) };
+    }
+
+    #[test]
+    fn pad_to_multiple_of_block() {
+        let mut bytes = UntypedBytes::from_slice([1u8; 10]);
+        bytes.pad_to_multiple_of(16, 0xff);
+        assert_eq!(bytes.len(), 16);
+        assert_eq!(unsafe { &bytes.as_slice()[..10] }, &[1; 10]);
+        assert_eq!(unsafe { &bytes.as_slice()[10..] }, &[0xff; 6]);
+
+        bytes.pad_to_multiple_of(16, 0);
+        assert_eq!(bytes.len(), 16);
+        bytes.pad_to_multiple_of(12, 0);
+        assert_eq!(bytes.len(), 24);
+        UntypedBytes::new().pad_to_multiple_of(512, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "block size must be non-zero")]
+    fn pad_to_multiple_of_zero_panics() {
+        UntypedBytes::new().pad_to_multiple_of(0, 0);
+    }
+
+    #[test]
+    fn reserve_and_zero_returns_prior_length() {
+        let mut bytes = UntypedBytes::from_slice([0xffu8; 3]);
+        let offset = bytes.reserve_and_zero(4);
+        assert_eq!(offset, 3);
+        assert_eq!(unsafe { bytes.as_slice() }, &[0xff, 0xff, 0xff, 0, 0, 0, 0]);
+        unsafe { bytes.update_at::<u32>(offset, |slot| *slot = 7) };
+        assert_eq!(unsafe { bytes.as_view().read_at::<u32>(offset) }, 7);
+        assert_eq!(bytes.reserve_and_zero(0), 7);
+    }
+
+    #[test]
+    fn uninit_fill_then_advance() {
+        let mut bytes = UntypedBytes::from_slice([9u8]);
+        let spare = bytes.as_uninit_mut(4);
+        assert_eq!(spare.len(), 4);
+        for (i, byte) in spare[..3].iter_mut().enumerate() {
+            *byte = MaybeUninit::new(i as u8 + 1);
+        }
+        unsafe { bytes.advance(3) };
+        assert_eq!(unsafe { bytes.as_slice() }, &[9, 1, 2, 3]);
+        assert!(bytes.capacity() >= 5);
+    }
+
+    #[test]
+    #[should_panic(expected = "`advance` past the end of the spare capacity")]
+    fn advance_past_capacity_panics() {
+        let mut bytes = UntypedBytes::with_capacity(4);
+        bytes.as_uninit_mut(4);
+        unsafe { bytes.advance(5) };
+    }
+
+    #[test]
+    fn write_slice_at_leaves_neighbours() {
+        let mut bytes = UntypedBytes::from_slice([0xaau8; 2]);
+        bytes.extend_from_slice([0u16; 4]);
+        bytes.extend_from_slice([0xbbu8; 2]);
+        unsafe { bytes.write_slice_at(3, &[0x1111u16, 0x2222]) };
+        assert_eq!(bytes.len(), 12);
+        let expected: Vec<u8> = [0xaa, 0xaa, 0]
+            .iter()
+            .copied()
+            .chain(0x1111u16.to_ne_bytes().iter().copied())
+            .chain(0x2222u16.to_ne_bytes().iter().copied())
+            .chain([0, 0, 0, 0xbb, 0xbb].iter().copied())
+            .collect();
+        assert_eq!(unsafe { bytes.as_slice() }, expected.as_slice());
+    }
+
+    #[test]
+    fn push_matrix_transposes_to_column_major() {
+        let row_major = [1u32, 2, 3, 4, 5, 6];
+        let mut bytes = UntypedBytes::new();
+        bytes.push_matrix(2, 3, &row_major, true);
+        assert_eq!(unsafe { bytes.to_vec_of::<u32, _>(..) }, [1, 4, 2, 5, 3, 6]);
+        bytes.clear();
+        bytes.push_matrix(2, 3, &row_major, false);
+        assert_eq!(unsafe { bytes.to_vec_of::<u32, _>(..) }, row_major);
+    }
+
+    #[test]
+    #[should_panic(expected = "matrix dimensions do not match")]
+    fn push_matrix_checks_dimensions() {
+        UntypedBytes::new().push_matrix(2, 2, &[0f32; 6], true);
+    }
+
+    #[test]
+    fn swap_ranges_only_touches_the_regions() {
+        let mut bytes = UntypedBytes::from_slice([0u8, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+        bytes.swap_ranges(1, 7, 4);
+        assert_eq!(
+            unsafe { bytes.as_slice() },
+            &[0, 7, 8, 9, 10, 5, 6, 1, 2, 3, 4, 11]
+        );
+        bytes.swap_ranges(7, 1, 4);
+        assert_eq!(
+            unsafe { bytes.as_slice() },
+            &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+        bytes.swap_ranges(3, 3, 0);
+        bytes.swap_ranges(0, 4, 4);
+        assert_eq!(
+            unsafe { bytes.as_slice() },
+            &[4, 5, 6, 7, 0, 1, 2, 3, 8, 9, 10, 11]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "`swap_ranges` regions overlap")]
+    fn swap_ranges_rejects_overlap() {
+        UntypedBytes::from_slice([0u8; 8]).swap_ranges(0, 3, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "`swap_ranges` out of bounds")]
+    fn swap_ranges_rejects_out_of_bounds() {
+        UntypedBytes::from_slice([0u8; 8]).swap_ranges(0, 5, 4);
+    }
+
+    #[test]
+    fn rotate_bytes() {
+        let original = [1u8, 2, 3, 4, 5];
+        let mut bytes = UntypedBytes::from_slice(original);
+        bytes.rotate_left(5);
+        assert_eq!(unsafe { bytes.as_slice() }, &original);
+        bytes.rotate_left(2);
+        assert_eq!(unsafe { bytes.as_slice() }, &[3, 4, 5, 1, 2]);
+        bytes.rotate_right(2);
+        assert_eq!(unsafe { bytes.as_slice() }, &original);
+        bytes.rotate_right(1);
+        assert_eq!(unsafe { bytes.as_slice() }, &[5, 1, 2, 3, 4]);
+        bytes.rotate_right(5);
+        assert_eq!(unsafe { bytes.as_slice() }, &[5, 1, 2, 3, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rotate_left_past_the_end_panics() {
+        UntypedBytes::from_slice([0u8; 3]).rotate_left(4);
+    }
+
+    #[test]
+    fn freeze_shares_bytes() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_from_slice([1u16, 2, 3]);
+        let frozen = bytes.freeze();
+        let mut expected = Vec::new();
+        for value in [1u16, 2, 3].iter() {
+            expected.extend_from_slice(&value.to_ne_bytes());
+        }
+        assert_eq!(&frozen[..], &expected[..]);
+        let clone = Arc::clone(&frozen);
+        assert_eq!(clone.as_ptr(), frozen.as_ptr());
+        assert_eq!(Arc::strong_count(&frozen), 2);
+    }
+
+    #[test]
+    fn fill_with_counter() {
+        let mut bytes = UntypedBytes::new();
+        let mut counter = 0u32;
+        bytes.fill_with(5, || {
+            counter += 1;
+            counter
+        });
+        assert_eq!(counter, 5);
+        assert_eq!(unsafe { bytes.to_vec_of::<u32, _>(..) }, [1, 2, 3, 4, 5]);
+
+        bytes.fill_with(0, || -> u32 { unreachable!() });
+        assert_eq!(bytes.len(), 20);
+    }
+
+    #[test]
+    fn fill_with_reserves_up_front() {
+        let mut bytes = UntypedBytes::from_slice([0u64]);
+        bytes.fill_with(100, || 7u64);
+        let capacity = bytes.capacity();
+        assert!(capacity >= 808);
+        let mut reserved = UntypedBytes::from_slice([0u64]);
+        reserved.reserve(800);
+        assert_eq!(capacity, reserved.capacity());
+    }
+
+    // Reference vectors for 64-bit FNV-1a.
+    #[test]
+    fn stable_hash_known_vectors() {
+        assert_eq!(UntypedBytes::new().stable_hash(), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(
+            UntypedBytes::from_slice(*b"a").stable_hash(),
+            0xaf63_dc4c_8601_ec8c
+        );
+        assert_eq!(
+            UntypedBytes::from_slice(*b"foobar").stable_hash(),
+            0x8594_4171_f739_67e8
+        );
+    }
+
+    #[test]
+    fn stable_hash_depends_only_on_bytes() {
+        let mut pushed = UntypedBytes::new();
+        pushed.push(0x0403_0201u32.to_le());
+        assert_eq!(
+            pushed.stable_hash(),
+            UntypedBytes::from_slice([1u8, 2, 3, 4]).stable_hash()
+        );
+        assert_ne!(
+            pushed.stable_hash(),
+            UntypedBytes::from_slice([4u8, 3, 2, 1]).stable_hash()
+        );
+    }
+
+    const CORNERS: [[f32; 2]; 4] = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+
+    #[test]
+    fn extend_from_indexed_gathers() {
+        let mut bytes = UntypedBytes::new();
+        bytes
+            .extend_from_indexed(&CORNERS, &[0u32, 1, 2, 2, 3, 0])
+            .unwrap();
+        bytes.extend_from_indexed(&CORNERS, &[3u16, 1]).unwrap();
+        let expected: Vec<[f32; 2]> = [0, 1, 2, 2, 3, 0, 3, 1]
+            .iter()
+            .map(|&i| CORNERS[i])
+            .collect();
+        assert_eq!(unsafe { bytes.to_vec_of::<[f32; 2], _>(..) }, expected);
+    }
+
+    #[test]
+    fn extend_from_indexed_out_of_range() {
+        let mut bytes = UntypedBytes::from_slice([CORNERS[0]]);
+        assert_eq!(
+            bytes.extend_from_indexed(&CORNERS, &[0u32, 3, 4, 9]),
+            Err(IndexOutOfRange {
+                position: 2,
+                len: 4
+            })
+        );
+        assert_eq!(
+            bytes.extend_from_indexed(&CORNERS, &[1i32, -1]),
+            Err(IndexOutOfRange {
+                position: 1,
+                len: 4
+            })
+        );
+        // Nothing was appended.
+        assert_eq!(bytes.len(), 8);
+        assert_eq!(
+            bytes.extend_from_indexed(&[] as &[u8], &[0u16]),
+            Err(IndexOutOfRange {
+                position: 0,
+                len: 0
+            })
+        );
+    }
+
+    #[test]
+    fn extend_from_indexed_empty() {
+        let mut bytes = UntypedBytes::new();
+        assert_eq!(bytes.extend_from_indexed(&CORNERS, &[] as &[u32]), Ok(()));
+        assert_eq!(
+            bytes.extend_from_indexed(&[] as &[u64], &[] as &[u16]),
+            Ok(())
+        );
+        assert!(bytes.is_empty());
+    }
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Interleaved {
+        position: [f32; 3],
+        uv: [f32; 2],
+        color: u32,
+    }
+
+    fn interleaved() -> Vec<Interleaved> {
+        (0..10)
+            .map(|i| Interleaved {
+                position: [i as f32; 3],
+                uv: [i as f32 / 10.0, 1.0 - i as f32 / 10.0],
+                color: 0xff00_0000 | i,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn extend_from_strided_slice_extracts_field() {
+        let vertices = interleaved();
+        let source = UntypedBytes::from_slice(&vertices[..]);
+        let stride = mem::size_of::<Interleaved>();
+        let mut uvs = UntypedBytes::new();
+        unsafe {
+            uvs.extend_from_strided_slice::<[f32; 2]>(source.as_slice(), 10, stride, 12)
+                .unwrap()
+        };
+        let expected: Vec<[f32; 2]> = vertices.iter().map(|v| v.uv).collect();
+        assert_eq!(unsafe { uvs.to_vec_of::<[f32; 2], _>(..) }, expected);
+
+        let mut colors = UntypedBytes::new();
+        unsafe {
+            colors.extend_from_strided::<u32>(source.as_slice().as_ptr(), 10, stride, 20);
+        }
+        let expected: Vec<u32> = vertices.iter().map(|v| v.color).collect();
+        assert_eq!(unsafe { colors.to_vec_of::<u32, _>(..) }, expected);
+    }
+
+    #[test]
+    fn extend_from_strided_slice_bounds() {
+        let source = UntypedBytes::from_slice(&interleaved()[..]);
+        let source = unsafe { source.as_slice() };
+        let mut bytes = UntypedBytes::new();
+        unsafe {
+            // The last color ends exactly at the end of the source.
+            assert_eq!(
+                bytes.extend_from_strided_slice::<u32>(source, 10, 24, 20),
+                Ok(())
+            );
+            assert_eq!(
+                bytes.extend_from_strided_slice::<u32>(source, 11, 24, 20),
+                Err(StrideError::OutOfBounds {
+                    required: 264,
+                    len: 240
+                })
+            );
+            assert_eq!(
+                bytes.extend_from_strided_slice::<u64>(source, 10, 24, 20),
+                Err(StrideError::OutOfBounds {
+                    required: 244,
+                    len: 240
+                })
+            );
+            assert_eq!(
+                bytes.extend_from_strided_slice::<u32>(source, 2, usize::MAX, 0),
+                Err(StrideError::Overflow)
+            );
+            assert_eq!(
+                bytes.extend_from_strided_slice::<u32>(&[], 0, 24, 100),
+                Ok(())
+            );
         }
+        assert_eq!(bytes.len(), 40);
     }
 }
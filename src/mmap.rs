@@ -0,0 +1,89 @@
+use crate::{UntypedBytes, UntypedSlice};
+use memmap2::Mmap;
+use std::{fs::File, io, ops::Deref, path::Path};
+
+/// A read-only memory-mapped file, created by `UntypedBytes::from_file_mmap`.
+///
+/// This shares the decoding accessors of `UntypedSlice` but none of the mutation API.
+#[derive(Debug)]
+pub struct MappedBytes {
+    map: Mmap,
+}
+
+impl UntypedBytes {
+    /// Maps the file at `path` into memory read-only, without copying it onto the heap.
+    ///
+    /// This is only safe if the file is not modified or truncated, by this process or any other,
+    /// while the returned `MappedBytes` is alive.
+    pub unsafe fn from_file_mmap(path: &Path) -> io::Result<MappedBytes> {
+        let file = File::open(path)?;
+        Ok(MappedBytes {
+            map: Mmap::map(&file)?,
+        })
+    }
+}
+
+impl MappedBytes {
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        UntypedSlice::new(&self.map)
+    }
+
+    /// See `UntypedSlice::cast`.
+    pub unsafe fn cast<T: Copy + Send + Sync + 'static>(&self) -> T {
+        self.as_view().cast()
+    }
+
+    /// See `UntypedSlice::read_at`.
+    pub unsafe fn read_at<T: Copy + Send + Sync + 'static>(&self, offset: usize) -> T {
+        self.as_view().read_at(offset)
+    }
+}
+
+impl Deref for MappedBytes {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{fs, path::PathBuf, process};
+
+    // A file in the temporary directory that is removed again when dropped.
+    struct TempFile(PathBuf);
+
+    impl TempFile {
+        fn new(name: &str, contents: &[u8]) -> Self {
+            let path =
+                std::env::temp_dir().join(format!("untyped-bytes-{}-{}", process::id(), name));
+            fs::write(&path, contents).unwrap();
+            TempFile(path)
+        }
+    }
+
+    impl Drop for TempFile {
+        fn drop(&mut self) {
+            let _ = fs::remove_file(&self.0);
+        }
+    }
+
+    #[test]
+    fn map_and_cast_file() {
+        let source = UntypedBytes::from_slice([1u32, 2, 3, 0xdead_beef]);
+        let file = TempFile::new("cast", unsafe { source.as_slice() });
+        let mapped = unsafe { UntypedBytes::from_file_mmap(&file.0) }.unwrap();
+        assert_eq!(&mapped[..], unsafe { source.as_slice() });
+        assert_eq!(unsafe { mapped.cast::<[u32; 4]>() }, [1, 2, 3, 0xdead_beef]);
+        assert_eq!(unsafe { mapped.read_at::<u32>(12) }, 0xdead_beef);
+        assert_eq!(mapped.as_view().len(), 16);
+    }
+
+    #[test]
+    fn missing_file_is_an_error() {
+        let path = std::env::temp_dir().join(format!("untyped-bytes-{}-missing", process::id()));
+        assert!(unsafe { UntypedBytes::from_file_mmap(&path) }.is_err());
+    }
+}
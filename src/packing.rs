@@ -0,0 +1,246 @@
+// Normalized integer vertex formats, converted as in the Vulkan and D3D specs: floats are clamped
+// to the representable range (NaN becomes 0) and scaled by the largest code, rounding to nearest
+// even. Snorm never encodes the most negative code, since -1.0 scales to -max and the extra code
+// decodes to -1.0 as well.
+
+use crate::{view, UntypedBytes};
+use alloc::vec::Vec;
+use core::{convert::TryInto, ops::RangeBounds};
+
+// Rounds to the nearest integer, ties to even, for `|x| < 2^23`, without `std`: adding 2^23
+// leaves no fractional bits, so the addition itself rounds under the default rounding mode.
+fn round_ties_even(x: f32) -> f32 {
+    const SHIFT: f32 = 8_388_608.0;
+    if x < 0.0 {
+        -((SHIFT - x) - SHIFT)
+    } else {
+        (x + SHIFT) - SHIFT
+    }
+}
+
+fn to_unorm(value: f32, max: f32) -> f32 {
+    let value = if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(0.0, 1.0)
+    };
+    round_ties_even(value * max)
+}
+
+fn to_snorm(value: f32, max: f32) -> f32 {
+    let value = if value.is_nan() {
+        0.0
+    } else {
+        value.clamp(-1.0, 1.0)
+    };
+    round_ties_even(value * max)
+}
+
+fn from_snorm(code: f32, max: f32) -> f32 {
+    (code / max).max(-1.0)
+}
+
+impl UntypedBytes {
+    /// Appends every value as a `u8` unorm code in `0..=255`.
+    pub fn extend_unorm8_from_f32(&mut self, values: &[f32]) {
+        self.extend_with(values.len(), |i| to_unorm(values[i], 255.0) as u8)
+    }
+
+    /// Appends every value as an `i8` snorm code in `-127..=127`.
+    pub fn extend_snorm8_from_f32(&mut self, values: &[f32]) {
+        self.extend_with(values.len(), |i| to_snorm(values[i], 127.0) as i8)
+    }
+
+    /// Appends every value as a native-endian `u16` unorm code in `0..=65535`.
+    pub fn extend_unorm16_from_f32(&mut self, values: &[f32]) {
+        self.extend_with(values.len(), |i| to_unorm(values[i], 65535.0) as u16)
+    }
+
+    /// Appends every value as a native-endian `i16` snorm code in `-32767..=32767`.
+    pub fn extend_snorm16_from_f32(&mut self, values: &[f32]) {
+        self.extend_with(values.len(), |i| to_snorm(values[i], 32767.0) as i16)
+    }
+
+    /// Appends `rgba` as a native-endian `u32` in the `R10G10B10A2_UNORM` layout: 10 bit unorm
+    /// red, green and blue from the least significant bit up, then 2 bit unorm alpha.
+    pub fn push_rgb10a2(&mut self, rgba: [f32; 4]) {
+        let [r, g, b, a] = rgba;
+        let packed = to_unorm(r, 1023.0) as u32
+            | (to_unorm(g, 1023.0) as u32) << 10
+            | (to_unorm(b, 1023.0) as u32) << 20
+            | (to_unorm(a, 3.0) as u32) << 30;
+        self.push(packed)
+    }
+
+    /// Decodes the unorm8 codes in `byte_range`. Panics if the range is out of bounds.
+    pub fn extract_f32_from_unorm8<R: RangeBounds<usize>>(&self, byte_range: R) -> Vec<f32> {
+        self.extract_packed(byte_range, "extract_f32_from_unorm8", |[code]| {
+            code as f32 / 255.0
+        })
+    }
+
+    /// Decodes the snorm8 codes in `byte_range`, with both -128 and -127 decoding to -1.0.
+    /// Panics if the range is out of bounds.
+    pub fn extract_f32_from_snorm8<R: RangeBounds<usize>>(&self, byte_range: R) -> Vec<f32> {
+        self.extract_packed(byte_range, "extract_f32_from_snorm8", |[code]| {
+            from_snorm(code as i8 as f32, 127.0)
+        })
+    }
+
+    /// Decodes the native-endian unorm16 codes in `byte_range`. Panics if the range is out of
+    /// bounds or its length is not a multiple of 2.
+    pub fn extract_f32_from_unorm16<R: RangeBounds<usize>>(&self, byte_range: R) -> Vec<f32> {
+        self.extract_packed(byte_range, "extract_f32_from_unorm16", |code| {
+            u16::from_ne_bytes(code) as f32 / 65535.0
+        })
+    }
+
+    /// Decodes the native-endian snorm16 codes in `byte_range`, with both -32768 and -32767
+    /// decoding to -1.0. Panics if the range is out of bounds or its length is not a multiple
+    /// of 2.
+    pub fn extract_f32_from_snorm16<R: RangeBounds<usize>>(&self, byte_range: R) -> Vec<f32> {
+        self.extract_packed(byte_range, "extract_f32_from_snorm16", |code| {
+            from_snorm(i16::from_ne_bytes(code) as f32, 32767.0)
+        })
+    }
+
+    /// Decodes the values written by `push_rgb10a2` in `byte_range`. Panics if the range is out
+    /// of bounds or its length is not a multiple of 4.
+    pub fn extract_rgb10a2<R: RangeBounds<usize>>(&self, byte_range: R) -> Vec<[f32; 4]> {
+        self.extract_packed(byte_range, "extract_rgb10a2", |code| {
+            let packed = u32::from_ne_bytes(code);
+            let channel = |shift: u32| (packed >> shift & 0x3ff) as f32 / 1023.0;
+            [
+                channel(0),
+                channel(10),
+                channel(20),
+                (packed >> 30) as f32 / 3.0,
+            ]
+        })
+    }
+
+    fn extract_packed<R, T, const N: usize>(
+        &self,
+        byte_range: R,
+        method: &str,
+        decode: impl Fn([u8; N]) -> T,
+    ) -> Vec<T>
+    where
+        R: RangeBounds<usize>,
+    {
+        let range = view::resolve(byte_range, self.len());
+        assert!(
+            range.len().is_multiple_of(N),
+            "`{}` range of {} bytes is not a multiple of {}",
+            method,
+            range.len(),
+            N
+        );
+        self.bytes[range]
+            .chunks_exact(N)
+            .map(|code| decode(code.try_into().unwrap()))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rounds_ties_to_even() {
+        assert_eq!(round_ties_even(0.5), 0.0);
+        assert_eq!(round_ties_even(1.5), 2.0);
+        assert_eq!(round_ties_even(2.5), 2.0);
+        assert_eq!(round_ties_even(2.500_001), 3.0);
+        assert_eq!(round_ties_even(-1.5), -2.0);
+        assert_eq!(round_ties_even(-2.5), -2.0);
+        assert_eq!(round_ties_even(127.5), 128.0);
+    }
+
+    #[test]
+    fn unorm8_boundaries() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_unorm8_from_f32(&[0.0, 1.0, -0.5, 2.0, 0.5, f32::NAN, 1.0 / 255.0]);
+        assert_eq!(unsafe { bytes.as_slice() }, &[0, 255, 0, 255, 128, 0, 1]);
+        assert_eq!(bytes.extract_f32_from_unorm8(..2), [0.0, 1.0]);
+    }
+
+    #[test]
+    fn snorm8_boundaries() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_snorm8_from_f32(&[1.0, -1.0, -2.0, 2.0, 0.0, -0.0, f32::NAN]);
+        let codes: Vec<i8> = unsafe { bytes.as_slice() }
+            .iter()
+            .map(|&b| b as i8)
+            .collect();
+        // -1.0 maps to -127, never to -128.
+        assert_eq!(codes, [127, -127, -127, 127, 0, 0, 0]);
+
+        let decoded = UntypedBytes::from_slice([-128i8, -127, 0, 127, 64]);
+        let floats = decoded.extract_f32_from_snorm8(..);
+        assert_eq!(&floats[..4], [-1.0, -1.0, 0.0, 1.0]);
+        assert_eq!(floats[4], 64.0 / 127.0);
+    }
+
+    #[test]
+    fn unorm16_boundaries() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_unorm16_from_f32(&[0.0, 1.0, -1.0, 1.5, 0.5]);
+        assert_eq!(
+            unsafe { bytes.to_vec_of::<u16, _>(..) },
+            [0, 65535, 0, 65535, 32768]
+        );
+        assert_eq!(bytes.extract_f32_from_unorm16(..4), [0.0, 1.0]);
+    }
+
+    #[test]
+    fn snorm16_boundaries() {
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_snorm16_from_f32(&[1.0, -1.0, -3.0, 0.0, f32::NAN]);
+        assert_eq!(
+            unsafe { bytes.to_vec_of::<i16, _>(..) },
+            [32767, -32767, -32767, 0, 0]
+        );
+        let decoded = UntypedBytes::from_slice([i16::MIN, -32767, 32767]);
+        assert_eq!(decoded.extract_f32_from_snorm16(..), [-1.0, -1.0, 1.0]);
+    }
+
+    #[test]
+    fn rgb10a2_layout() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push_rgb10a2([1.0, 0.0, 0.5, 1.0]);
+        bytes.push_rgb10a2([-1.0, 2.0, f32::NAN, 1.0 / 3.0]);
+        assert_eq!(
+            unsafe { bytes.to_vec_of::<u32, _>(..) },
+            [0x3ff | 512 << 20 | 3 << 30, 0x3ff << 10 | 1 << 30]
+        );
+        assert_eq!(
+            bytes.extract_rgb10a2(..),
+            [[1.0, 0.0, 512.0 / 1023.0, 1.0], [0.0, 1.0, 0.0, 1.0 / 3.0]]
+        );
+    }
+
+    // Every code survives a decode and re-encode.
+    #[test]
+    fn codes_round_trip() {
+        let codes: Vec<u8> = (0..=255).collect();
+        let bytes = UntypedBytes::from_slice(&codes[..]);
+        let mut unorm = UntypedBytes::new();
+        unorm.extend_unorm8_from_f32(&bytes.extract_f32_from_unorm8(..));
+        assert_eq!(unsafe { unorm.as_slice() }, &codes[..]);
+
+        let mut snorm = UntypedBytes::new();
+        snorm.extend_snorm8_from_f32(&bytes.extract_f32_from_snorm8(..));
+        let mut expected = codes.clone();
+        // -128 decodes to -1.0, which encodes as -127.
+        expected[128] = -127i8 as u8;
+        assert_eq!(unsafe { snorm.as_slice() }, &expected[..]);
+    }
+
+    #[test]
+    #[should_panic(expected = "`extract_rgb10a2` range of 6 bytes is not a multiple of 4")]
+    fn partial_code_panics() {
+        UntypedBytes::from_slice([0u16; 3]).extract_rgb10a2(..);
+    }
+}
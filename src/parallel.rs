@@ -0,0 +1,75 @@
+use crate::{as_bytes_slice, UntypedBytes};
+use rayon::prelude::*;
+use std::{borrow::Borrow, mem, ptr};
+
+impl UntypedBytes {
+    /// Like `extend_from_slices`, but copies the slices in parallel. Returns the offset of the
+    /// first appended byte.
+    ///
+    /// The destination of each slice is computed up front and the spare capacity is split into
+    /// one disjoint region per slice, so no two copies ever write to the same bytes.
+    pub fn par_extend_from_slices<T, V>(&mut self, slices: &[V]) -> usize
+    where
+        T: Copy + Send + Sync + 'static,
+        V: Borrow<[T]> + Sync,
+    {
+        let offset = self.len();
+        let total = slices
+            .iter()
+            .try_fold(0usize, |total, slice| {
+                total.checked_add(mem::size_of_val(slice.borrow()))
+            })
+            .expect("capacity overflow");
+        self.reserve(total);
+        let mut spare = &mut self.bytes.spare_capacity_mut()[..total];
+        let mut regions = Vec::with_capacity(slices.len());
+        for slice in slices {
+            let raw = unsafe { as_bytes_slice(slice.borrow()) };
+            let (region, rest) = mem::take(&mut spare).split_at_mut(raw.len());
+            regions.push((region, raw));
+            spare = rest;
+        }
+        regions.into_par_iter().for_each(|(region, raw)| unsafe {
+            ptr::copy_nonoverlapping(raw.as_ptr(), region.as_mut_ptr() as *mut u8, raw.len())
+        });
+        unsafe { self.bytes.set_len(offset + total) }
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.record::<T>(offset..offset + total);
+        offset
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slices() -> Vec<Vec<[u32; 3]>> {
+        (0..64u32)
+            .map(|i| (0..i * 7 % 23).map(|j| [i, j, i ^ j]).collect())
+            .collect()
+    }
+
+    #[test]
+    fn matches_serial() {
+        let slices = slices();
+        let mut serial = UntypedBytes::from_slice([[9u32; 3]]);
+        let mut parallel = serial.clone();
+        assert_eq!(serial.extend_from_slices(&slices), 12);
+        assert_eq!(parallel.par_extend_from_slices(&slices), 12);
+        assert_eq!(parallel.len(), serial.len());
+        assert_eq!(unsafe { parallel.as_slice() }, unsafe { serial.as_slice() });
+        assert_eq!(unsafe { parallel.to_vec_of::<[u32; 3], _>(..) }, unsafe {
+            serial.to_vec_of::<[u32; 3], _>(..)
+        });
+    }
+
+    #[test]
+    fn borrowed_and_empty_slices() {
+        let mut bytes = UntypedBytes::new();
+        let none: [&[u16]; 0] = [];
+        assert_eq!(bytes.par_extend_from_slices(&none), 0);
+        let some: [&[u16]; 3] = [&[1, 2], &[], &[3]];
+        assert_eq!(bytes.par_extend_from_slices(&some), 0);
+        assert_eq!(unsafe { bytes.to_vec_of::<u16, _>(..) }, [1, 2, 3]);
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{CastError, UntypedBytes, UntypedSlice};
+use alloc::vec::Vec;
+use bytemuck::{AnyBitPattern, NoUninit};
+use core::{borrow::Borrow, convert::TryFrom, mem, ops::RangeBounds};
+
+/// An `UntypedBytes` that only ever holds `bytemuck::NoUninit` values, so it has no padding or
+/// other uninitialized bytes and can be read back as any `bytemuck::AnyBitPattern` type without
+/// `unsafe`.
+///
+/// The API mirrors `UntypedBytes`, with `NoUninit` bounds on the writes and `AnyBitPattern`
+/// bounds on the reads in place of the `unsafe` contracts. Available with the `safe` feature.
+#[derive(Clone, Debug, Default)]
+pub struct PodBytes {
+    bytes: UntypedBytes,
+}
+
+impl PodBytes {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        PodBytes {
+            bytes: UntypedBytes::with_capacity(capacity),
+        }
+    }
+
+    pub fn from_slice<T, V>(value: V) -> Self
+    where
+        T: NoUninit + Send + Sync,
+        V: Borrow<[T]>,
+    {
+        PodBytes {
+            bytes: UntypedBytes::from_slice(value),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.bytes.capacity()
+    }
+
+    pub fn reserve(&mut self, additional: usize) {
+        self.bytes.reserve(additional)
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.clear()
+    }
+
+    pub fn push<T: NoUninit + Send + Sync>(&mut self, value: T) {
+        self.bytes.push(value)
+    }
+
+    pub fn extend_from_slice<T, V>(&mut self, value: V)
+    where
+        T: NoUninit + Send + Sync,
+        V: Borrow<[T]>,
+    {
+        self.bytes.extend_from_slice(value)
+    }
+
+    /// The backing bytes, which are always safe to inspect.
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { self.bytes.as_slice() }
+    }
+
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        self.bytes.as_view()
+    }
+
+    /// Casts the backing bytes to a value of type `T`. Panics if the sizes differ.
+    pub fn cast<T: AnyBitPattern + Send + Sync>(&self) -> T {
+        match self.try_cast() {
+            Ok(value) => value,
+            Err(err) => panic!("`PodBytes::cast` failed: {}", err),
+        }
+    }
+
+    /// Like `cast`, but returns an error instead of panicking if the sizes differ.
+    pub fn try_cast<T: AnyBitPattern + Send + Sync>(&self) -> Result<T, CastError> {
+        // Any bit pattern is a valid `T`, so unlike `UntypedBytes::try_cast` this does not go
+        // through the `debug-typecheck` check.
+        if self.len() != mem::size_of::<T>() {
+            return Err(CastError::SizeMismatch {
+                expected: mem::size_of::<T>(),
+                found: self.len(),
+            });
+        }
+        Ok(self.read_at(0))
+    }
+
+    /// Copies the backing bytes out into a `Vec<T>`, returning an error if the length is not a
+    /// multiple of the size of `T`.
+    pub fn try_cast_vec<T: AnyBitPattern + Send + Sync>(&self) -> Result<Vec<T>, CastError> {
+        let size = mem::size_of::<T>();
+        assert_ne!(size, 0, "elements must not be zero-sized");
+        if !self.len().is_multiple_of(size) {
+            return Err(CastError::NotAMultiple {
+                element_size: size,
+                found: self.len(),
+            });
+        }
+        Ok(self.to_vec_of(..))
+    }
+
+    /// Reads a value of type `T` starting at `offset`, regardless of alignment. Panics if the read
+    /// is out of bounds.
+    pub fn read_at<T: AnyBitPattern + Send + Sync>(&self, offset: usize) -> T {
+        unsafe { self.as_view().read_at(offset) }
+    }
+
+    /// Like `read_at`, but returns an error instead of panicking if the read is out of bounds.
+    pub fn try_read_at<T: AnyBitPattern + Send + Sync>(
+        &self,
+        offset: usize,
+    ) -> Result<T, CastError> {
+        unsafe { self.as_view().try_read_at(offset) }
+    }
+
+    /// Copies the given range of `T` elements out into a `Vec<T>`. Panics if the length is not a
+    /// multiple of the size of `T` or the range is out of bounds.
+    pub fn to_vec_of<T, R>(&self, range: R) -> Vec<T>
+    where
+        T: AnyBitPattern + Send + Sync,
+        R: RangeBounds<usize>,
+    {
+        unsafe { self.bytes.to_vec_of(range) }
+    }
+
+    /// Returns the backing buffer, for use with the rest of the `UntypedBytes` API.
+    pub fn into_inner(self) -> UntypedBytes {
+        self.bytes
+    }
+}
+
+impl AsRef<UntypedBytes> for PodBytes {
+    fn as_ref(&self) -> &UntypedBytes {
+        &self.bytes
+    }
+}
+
+impl From<PodBytes> for UntypedBytes {
+    fn from(value: PodBytes) -> Self {
+        value.bytes
+    }
+}
+
+// A blanket `impl<T> TryFrom<&PodBytes> for T` is ruled out by the orphan rules, so only the
+// primitives get one. Other types can use `try_cast` and `try_cast_vec`.
+macro_rules! impl_try_from {
+    ($($ty:ty),*) => {
+        $(
+            impl TryFrom<&PodBytes> for $ty {
+                type Error = CastError;
+
+                fn try_from(value: &PodBytes) -> Result<Self, Self::Error> {
+                    value.try_cast()
+                }
+            }
+
+            impl TryFrom<&PodBytes> for Vec<$ty> {
+                type Error = CastError;
+
+                fn try_from(value: &PodBytes) -> Result<Self, Self::Error> {
+                    value.try_cast_vec()
+                }
+            }
+        )*
+    };
+}
+
+impl_try_from!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bytemuck::{Pod, Zeroable};
+    use core::convert::TryInto;
+
+    #[repr(C)]
+    #[derive(Clone, Copy, Debug, PartialEq, Pod, Zeroable)]
+    struct Vertex {
+        position: [f32; 3],
+        color: u32,
+    }
+
+    const VERTICES: [Vertex; 2] = [
+        Vertex {
+            position: [0.0, 1.0, 2.0],
+            color: 0xff00_00ff,
+        },
+        Vertex {
+            position: [3.0, 4.0, 5.0],
+            color: 0x00ff_00ff,
+        },
+    ];
+
+    // Goes through the safe API alone, without a single `unsafe` block.
+    #[test]
+    fn pod_round_trip() {
+        let mut bytes = PodBytes::new();
+        bytes.push(VERTICES[0]);
+        bytes.extend_from_slice(&VERTICES[1..]);
+        assert_eq!(bytes.len(), 2 * mem::size_of::<Vertex>());
+        assert_eq!(bytes.try_cast_vec::<Vertex>(), Ok(VERTICES.to_vec()));
+        assert_eq!(
+            bytes.read_at::<Vertex>(mem::size_of::<Vertex>()),
+            VERTICES[1]
+        );
+        assert_eq!(bytes.to_vec_of::<Vertex, _>(1..), VERTICES[1..].to_vec());
+        assert_eq!(bytes.read_at::<f32>(4), 1.0);
+        assert_eq!(&bytes.as_slice()[12..16], &0xff00_00ffu32.to_ne_bytes());
+    }
+
+    #[test]
+    fn cast_checks_size() {
+        let bytes = PodBytes::from_slice([VERTICES[0]]);
+        assert_eq!(bytes.cast::<Vertex>(), VERTICES[0]);
+        assert_eq!(
+            bytes.try_cast::<u64>(),
+            Err(CastError::SizeMismatch {
+                expected: 8,
+                found: 16
+            })
+        );
+        assert_eq!(
+            bytes.try_read_at::<u64>(12),
+            Err(CastError::OutOfBounds {
+                offset: 12,
+                size: 8,
+                len: 16
+            })
+        );
+        assert!(bytes.try_cast_vec::<[u8; 3]>().is_err());
+    }
+
+    #[test]
+    #[should_panic(expected = "`PodBytes::cast` failed")]
+    fn cast_panics_on_size_mismatch() {
+        PodBytes::from_slice([1u16, 2, 3]).cast::<u32>();
+    }
+
+    #[test]
+    fn into_inner_keeps_bytes() {
+        let bytes = PodBytes::from_slice([1u32, 2]);
+        let inner: UntypedBytes = bytes.clone().into();
+        assert_eq!(inner.len(), 8);
+        assert!(inner.starts_with(bytes.as_slice()));
+    }
+
+    #[test]
+    fn try_from_primitive() {
+        let bytes = PodBytes::from_slice([0x1234_5678u32]);
+        assert_eq!(u32::try_from(&bytes), Ok(0x1234_5678));
+        assert_eq!(f32::try_from(&bytes), Ok(f32::from_bits(0x1234_5678)));
+        assert_eq!(
+            u64::try_from(&bytes),
+            Err(CastError::SizeMismatch {
+                expected: 8,
+                found: 4
+            })
+        );
+        let value: Result<u16, _> = (&bytes).try_into();
+        assert!(value.is_err());
+    }
+
+    #[test]
+    fn try_from_vec() {
+        let bytes = PodBytes::from_slice([1u32, 2, 3]);
+        assert_eq!(Vec::<u32>::try_from(&bytes), Ok(vec![1, 2, 3]));
+        assert_eq!(Vec::<u8>::try_from(&bytes).map(|v| v.len()), Ok(12));
+        assert_eq!(
+            Vec::<u64>::try_from(&bytes),
+            Err(CastError::NotAMultiple {
+                element_size: 8,
+                found: 12
+            })
+        );
+    }
+}
@@ -0,0 +1,285 @@
+use crate::UntypedBytes;
+use std::{
+    mem,
+    ops::{Deref, DerefMut},
+    sync::{Mutex, MutexGuard},
+};
+
+/// A thread-safe pool of retired buffers, so short lived buffers of similar sizes can reuse
+/// allocations instead of going back to the allocator.
+///
+/// Buffers are bucketed by power of two capacity class. Returned buffers are dropped instead of
+/// retained once the pool holds its limit of buffers or bytes.
+#[derive(Debug)]
+pub struct BytesPool {
+    state: Mutex<PoolState>,
+    max_buffers: usize,
+    max_bytes: usize,
+    shrink_factor: Option<usize>,
+}
+
+#[derive(Debug, Default)]
+struct PoolState {
+    // `buckets[class]` holds buffers whose capacity is at least `1 << class`.
+    buckets: Vec<Vec<Vec<u8>>>,
+    buffers: usize,
+    bytes: usize,
+}
+
+impl Default for BytesPool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BytesPool {
+    /// Creates a pool with no limits on the number of buffers or bytes retained.
+    pub fn new() -> Self {
+        Self::with_limits(usize::MAX, usize::MAX)
+    }
+
+    /// Creates a pool that retains at most `max_buffers` buffers with a total capacity of at most
+    /// `max_bytes`.
+    pub fn with_limits(max_buffers: usize, max_bytes: usize) -> Self {
+        BytesPool {
+            state: Default::default(),
+            max_buffers,
+            max_bytes,
+            shrink_factor: None,
+        }
+    }
+
+    /// Shrinks returned buffers whose capacity grew to more than `factor` times their capacity
+    /// class back down to the class, so one oversized use does not pin a large allocation.
+    pub fn with_shrink_factor(mut self, factor: usize) -> Self {
+        assert!(
+            factor >= 1,
+            "`with_shrink_factor` requires a factor of at least 1"
+        );
+        self.shrink_factor = Some(factor);
+        self
+    }
+
+    /// Returns an empty buffer with a capacity of at least `min_capacity`, reusing a retired
+    /// allocation if one is available.
+    pub fn get(&self, min_capacity: usize) -> PooledBytes<'_> {
+        let class = capacity_class(min_capacity);
+        let reused = {
+            let mut state = self.lock();
+            let bytes = state.buckets.get_mut(class).and_then(Vec::pop);
+            if let Some(bytes) = &bytes {
+                state.buffers -= 1;
+                state.bytes -= bytes.capacity();
+            }
+            bytes
+        };
+        let bytes = reused.unwrap_or_else(|| Vec::with_capacity(1 << class));
+        PooledBytes {
+            bytes: UntypedBytes::from_byte_vec(bytes),
+            class,
+            pool: self,
+        }
+    }
+
+    /// The number of buffers currently retained.
+    pub fn retained_buffers(&self) -> usize {
+        self.lock().buffers
+    }
+
+    /// The total capacity of the buffers currently retained.
+    pub fn retained_bytes(&self) -> usize {
+        self.lock().bytes
+    }
+
+    fn put(&self, mut bytes: Vec<u8>, class: usize) {
+        bytes.clear();
+        if let Some(factor) = self.shrink_factor {
+            let class_capacity = 1 << class;
+            if bytes.capacity() / factor > class_capacity {
+                bytes.shrink_to(class_capacity);
+            }
+        }
+        if bytes.capacity() == 0 {
+            return;
+        }
+        let mut state = self.lock();
+        if state.buffers >= self.max_buffers || self.max_bytes - state.bytes < bytes.capacity() {
+            return;
+        }
+        let class = floor_class(bytes.capacity());
+        if state.buckets.len() <= class {
+            state.buckets.resize_with(class + 1, Vec::new);
+        }
+        state.buffers += 1;
+        state.bytes += bytes.capacity();
+        state.buckets[class].push(bytes);
+    }
+
+    fn lock(&self) -> MutexGuard<'_, PoolState> {
+        self.state
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
+
+// The smallest class whose capacity is at least `capacity`.
+fn capacity_class(capacity: usize) -> usize {
+    capacity.max(1).next_power_of_two().trailing_zeros() as usize
+}
+
+// The largest class whose capacity is at most `capacity`, which must be nonzero.
+fn floor_class(capacity: usize) -> usize {
+    (usize::BITS - 1 - capacity.leading_zeros()) as usize
+}
+
+/// A buffer borrowed from a `BytesPool`, created by `BytesPool::get`. Its allocation is returned
+/// to the pool when it is dropped.
+#[derive(Debug)]
+pub struct PooledBytes<'a> {
+    bytes: UntypedBytes,
+    class: usize,
+    pool: &'a BytesPool,
+}
+
+impl PooledBytes<'_> {
+    /// Takes the buffer out of the pool's management, so it is not returned on drop.
+    pub fn into_inner(mut self) -> UntypedBytes {
+        let bytes = mem::take(&mut self.bytes);
+        mem::forget(self);
+        bytes
+    }
+}
+
+impl Deref for PooledBytes<'_> {
+    type Target = UntypedBytes;
+
+    fn deref(&self) -> &UntypedBytes {
+        &self.bytes
+    }
+}
+
+impl DerefMut for PooledBytes<'_> {
+    fn deref_mut(&mut self) -> &mut UntypedBytes {
+        &mut self.bytes
+    }
+}
+
+impl Drop for PooledBytes<'_> {
+    fn drop(&mut self) {
+        let bytes = mem::take(&mut self.bytes.bytes);
+        self.pool.put(bytes, self.class)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{sync::Arc, thread};
+
+    fn ptr(bytes: &UntypedBytes) -> *const u8 {
+        bytes.bytes.as_ptr()
+    }
+
+    #[test]
+    fn reuses_allocations() {
+        let pool = BytesPool::new();
+        let mut first = pool.get(100);
+        assert!(first.capacity() >= 100);
+        first.extend_from_slice([1u32, 2, 3]);
+        let first_ptr = ptr(&first);
+        drop(first);
+        assert_eq!(pool.retained_buffers(), 1);
+        assert_eq!(pool.retained_bytes(), 128);
+
+        // Any request in the same capacity class gets the same allocation back, empty.
+        let second = pool.get(70);
+        assert_eq!(ptr(&second), first_ptr);
+        assert!(second.is_empty());
+        assert_eq!(pool.retained_buffers(), 0);
+        assert_eq!(pool.retained_bytes(), 0);
+
+        // A larger class is not served from a smaller buffer.
+        let third = pool.get(1000);
+        assert!(third.capacity() >= 1000);
+        assert_ne!(ptr(&third), first_ptr);
+    }
+
+    #[test]
+    fn larger_buffers_serve_smaller_classes() {
+        let pool = BytesPool::new();
+        let mut grown = pool.get(16);
+        grown.extend_from_slice([0u8; 300]);
+        let grown_ptr = ptr(&grown);
+        drop(grown);
+        // The buffer grew past 256 bytes, so it is filed under that class.
+        assert_eq!(ptr(&pool.get(200)), grown_ptr);
+    }
+
+    #[test]
+    fn enforces_buffer_limit() {
+        let pool = BytesPool::with_limits(2, usize::MAX);
+        let buffers: Vec<_> = (0..3).map(|_| pool.get(64)).collect();
+        drop(buffers);
+        assert_eq!(pool.retained_buffers(), 2);
+        assert_eq!(pool.retained_bytes(), 128);
+    }
+
+    #[test]
+    fn enforces_byte_limit() {
+        let pool = BytesPool::with_limits(usize::MAX, 300);
+        drop(pool.get(256));
+        drop(pool.get(64));
+        assert_eq!(pool.retained_bytes(), 256);
+        let (a, b) = (pool.get(32), pool.get(32));
+        drop((a, b));
+        assert_eq!(pool.retained_buffers(), 2);
+        assert_eq!(pool.retained_bytes(), 288);
+        drop(pool.get(64));
+        assert!(pool.retained_bytes() <= 300);
+    }
+
+    #[test]
+    fn shrinks_oversized_buffers() {
+        let pool = BytesPool::new().with_shrink_factor(4);
+        let mut bytes = pool.get(16);
+        bytes.extend_from_slice([0u8; 1000]);
+        drop(bytes);
+        assert!(pool.retained_bytes() < 64);
+
+        let mut bytes = pool.get(16);
+        bytes.extend_from_slice([0u8; 40]);
+        drop(bytes);
+        assert!(pool.retained_bytes() >= 40);
+    }
+
+    #[test]
+    fn into_inner_is_not_returned() {
+        let pool = BytesPool::new();
+        let mut bytes = pool.get(8).into_inner();
+        bytes.push(1u64);
+        drop(bytes);
+        assert_eq!(pool.retained_buffers(), 0);
+    }
+
+    #[test]
+    fn shared_between_threads() {
+        let pool = Arc::new(BytesPool::with_limits(4, usize::MAX));
+        let workers: Vec<_> = (0..8u32)
+            .map(|i| {
+                let pool = Arc::clone(&pool);
+                thread::spawn(move || {
+                    for _ in 0..20 {
+                        let mut bytes = pool.get(256);
+                        assert!(bytes.is_empty());
+                        bytes.push([i; 64]);
+                        assert_eq!(unsafe { bytes.cast::<[u32; 64]>() }, [i; 64]);
+                    }
+                })
+            })
+            .collect();
+        for worker in workers {
+            worker.join().unwrap();
+        }
+        assert!(pool.retained_buffers() <= 4);
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{as_bytes, as_bytes_slice, UntypedBytes};
+use core::{marker::PhantomData, mem::MaybeUninit, ptr};
+
+/// A destination that typed values can be appended to as raw bytes.
+///
+/// Implemented by `UntypedBytes`, which grows as needed, and by `FixedWriter`, which writes into
+/// a preallocated region of memory.
+pub trait PushBytes {
+    fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T);
+
+    fn extend_from_slice<T: Copy + Send + Sync + 'static>(&mut self, values: &[T]);
+
+    /// The number of bytes written so far.
+    fn written(&self) -> usize;
+}
+
+impl PushBytes for UntypedBytes {
+    #[inline]
+    fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        UntypedBytes::push(self, value)
+    }
+
+    #[inline]
+    fn extend_from_slice<T: Copy + Send + Sync + 'static>(&mut self, values: &[T]) {
+        UntypedBytes::extend_from_slice(self, values)
+    }
+
+    #[inline]
+    fn written(&self) -> usize {
+        self.len()
+    }
+}
+
+/// Writes into a fixed-size region of memory, such as a mapped GPU buffer, from the start.
+/// Writing past the end of the region panics.
+#[derive(Debug)]
+pub struct FixedWriter<'a> {
+    ptr: *mut u8,
+    len: usize,
+    written: usize,
+    _marker: PhantomData<&'a mut [u8]>,
+}
+
+unsafe impl Send for FixedWriter<'_> {}
+unsafe impl Sync for FixedWriter<'_> {}
+
+impl<'a> FixedWriter<'a> {
+    pub fn new(dst: &'a mut [u8]) -> Self {
+        FixedWriter {
+            ptr: dst.as_mut_ptr(),
+            len: dst.len(),
+            written: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn from_uninit(dst: &'a mut [MaybeUninit<u8>]) -> Self {
+        FixedWriter {
+            ptr: dst.as_mut_ptr() as *mut u8,
+            len: dst.len(),
+            written: 0,
+            _marker: PhantomData,
+        }
+    }
+
+    /// The number of bytes that can still be written.
+    pub fn remaining(&self) -> usize {
+        self.len - self.written
+    }
+
+    fn write_raw(&mut self, raw: &[u8]) {
+        assert!(
+            raw.len() <= self.remaining(),
+            "`FixedWriter` overflow: writing {} bytes with {} remaining",
+            raw.len(),
+            self.remaining()
+        );
+        unsafe {
+            ptr::copy_nonoverlapping(raw.as_ptr(), self.ptr.add(self.written), raw.len());
+        }
+        self.written += raw.len();
+    }
+}
+
+impl PushBytes for FixedWriter<'_> {
+    #[inline]
+    fn push<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        self.write_raw(unsafe { as_bytes(&value) })
+    }
+
+    #[inline]
+    fn extend_from_slice<T: Copy + Send + Sync + 'static>(&mut self, values: &[T]) {
+        self.write_raw(unsafe { as_bytes_slice(values) })
+    }
+
+    #[inline]
+    fn written(&self) -> usize {
+        self.written
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // One encoder, written once against the trait.
+    fn encode(out: &mut impl PushBytes) {
+        out.push(0x1234_5678u32);
+        out.extend_from_slice(&[1u16, 2, 3]);
+        out.push(9u8);
+        assert_eq!(out.written(), 11);
+    }
+
+    #[test]
+    fn growable_and_fixed_sinks_agree() {
+        let mut bytes = UntypedBytes::new();
+        encode(&mut bytes);
+
+        let mut mapped = [0xaau8; 16];
+        let mut writer = FixedWriter::new(&mut mapped);
+        encode(&mut writer);
+        assert_eq!(writer.remaining(), 5);
+        assert_eq!(&mapped[..11], unsafe { bytes.as_slice() });
+        assert_eq!(&mapped[11..], &[0xaa; 5]);
+
+        let mut uninit = [MaybeUninit::<u8>::uninit(); 11];
+        let mut writer = FixedWriter::from_uninit(&mut uninit);
+        encode(&mut writer);
+        assert_eq!(writer.remaining(), 0);
+        let written = unsafe { &*(&uninit as *const [MaybeUninit<u8>; 11] as *const [u8; 11]) };
+        assert_eq!(&written[..], unsafe { bytes.as_slice() });
+    }
+
+    #[test]
+    #[should_panic(expected = "`FixedWriter` overflow")]
+    fn fixed_writer_panics_on_overflow() {
+        let mut mapped = [0u8; 10];
+        encode(&mut FixedWriter::new(&mut mapped));
+    }
+}
@@ -0,0 +1,48 @@
+use crate::UntypedBytes;
+
+/// Types that can be written into an `UntypedBytes` one field at a time, with no padding between
+/// fields.
+///
+/// Unlike `UntypedBytes::push`, the written bytes never contain struct padding, so they are safe
+/// to inspect. Use `#[derive(PushFields)]` (feature `derive`) to implement this for structs.
+pub trait PushFields {
+    /// Appends the fields of `self` to `out` in declaration order.
+    fn push_fields(&self, out: &mut UntypedBytes);
+
+    /// The number of bytes written by `push_fields`.
+    fn packed_size() -> usize;
+}
+
+macro_rules! impl_push_fields {
+    ($($ty:ty),*) => {
+        $(
+            impl PushFields for $ty {
+                #[inline]
+                fn push_fields(&self, out: &mut UntypedBytes) {
+                    out.push(*self)
+                }
+
+                #[inline]
+                fn packed_size() -> usize {
+                    core::mem::size_of::<$ty>()
+                }
+            }
+        )*
+    };
+}
+
+impl_push_fields!(
+    u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64, bool, char
+);
+
+impl<T: PushFields, const N: usize> PushFields for [T; N] {
+    fn push_fields(&self, out: &mut UntypedBytes) {
+        for elem in self {
+            elem.push_fields(out)
+        }
+    }
+
+    fn packed_size() -> usize {
+        T::packed_size() * N
+    }
+}
@@ -0,0 +1,89 @@
+use crate::UntypedBytes;
+use core::mem;
+
+mod private {
+    pub trait Sealed {}
+}
+
+/// Tuples of values that can be pushed together with `UntypedBytes::push_all`.
+///
+/// Implemented for tuples of up to 12 `Copy + Send + Sync + 'static` elements.
+pub trait PushTuple: private::Sealed {
+    #[doc(hidden)]
+    const SIZE: usize;
+
+    #[doc(hidden)]
+    fn push_into(self, out: &mut UntypedBytes);
+}
+
+macro_rules! impl_push_tuple {
+    ($(($($name:ident: $ty:ident),+))*) => {
+        $(
+            impl<$($ty: Copy + Send + Sync + 'static),+> private::Sealed for ($($ty,)+) {}
+
+            impl<$($ty: Copy + Send + Sync + 'static),+> PushTuple for ($($ty,)+) {
+                const SIZE: usize = 0 $(+ mem::size_of::<$ty>())+;
+
+                #[inline]
+                fn push_into(self, out: &mut UntypedBytes) {
+                    let ($($name,)+) = self;
+                    $(out.push($name);)+
+                }
+            }
+        )*
+    };
+}
+
+impl_push_tuple! {
+    (a: A)
+    (a: A, b: B)
+    (a: A, b: B, c: C)
+    (a: A, b: B, c: C, d: D)
+    (a: A, b: B, c: C, d: D, e: E)
+    (a: A, b: B, c: C, d: D, e: E, f: F)
+    (a: A, b: B, c: C, d: D, e: E, f: F, g: G)
+    (a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H)
+    (a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I)
+    (a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J)
+    (a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J, k: K)
+    (a: A, b: B, c: C, d: D, e: E, f: F, g: G, h: H, i: I, j: J, k: K, l: L)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn push_all_matches_individual_pushes() {
+        let mut tupled = UntypedBytes::from_slice([0xffu8]);
+        let offset = tupled.push_all((1u8, 2u32, 3.0f64, [4u16; 3]));
+        assert_eq!(offset, 1);
+        let mut pushed = UntypedBytes::from_slice([0xffu8]);
+        pushed.push(1u8);
+        pushed.push(2u32);
+        pushed.push(3.0f64);
+        pushed.push([4u16; 3]);
+        assert_eq!(unsafe { tupled.as_slice() }, unsafe { pushed.as_slice() });
+        assert_eq!(<(u8, u32, f64, [u16; 3])>::SIZE, 19);
+    }
+
+    // Pushing these one at a time would grow an empty buffer to 8 and then 16 bytes, so a
+    // capacity of exactly 15 shows there was a single reservation.
+    #[test]
+    fn push_all_reserves_once() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push_all((1u8, 2u32, 3u64, 4u16));
+        assert_eq!(bytes.len(), 15);
+        assert_eq!(bytes.capacity(), 15);
+    }
+
+    #[test]
+    fn push_all_largest_tuple() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push_all((0u8, 1u8, 2u8, 3u8, 4u8, 5u8, 6u8, 7u8, 8u8, 9u8, 10u8, 11u8));
+        assert_eq!(
+            unsafe { bytes.as_slice() },
+            &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]
+        );
+    }
+}
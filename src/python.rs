@@ -0,0 +1,178 @@
+use crate::UntypedBytes;
+use pyo3::{buffer::PyUntypedBuffer, exceptions::PyBufferError, ffi, prelude::*, types::PyBytes};
+use std::{
+    ffi::{c_char, c_int, c_void},
+    ptr, slice,
+};
+
+impl UntypedBytes {
+    /// Copies the bytes of any C-contiguous Python object supporting the buffer protocol, such as
+    /// `bytes`, `bytearray`, or a numpy array.
+    pub fn from_pybuffer(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        let buffer = PyUntypedBuffer::get(obj)?;
+        if !buffer.is_c_contiguous() {
+            return Err(PyBufferError::new_err("buffer is not C-contiguous"));
+        }
+        let len = buffer.len_bytes();
+        let bytes = if len == 0 {
+            Vec::new()
+        } else {
+            unsafe { slice::from_raw_parts(buffer.buf_ptr() as *const u8, len) }.to_vec()
+        };
+        Ok(Self::from_byte_vec(bytes))
+    }
+
+    /// Copies the backing bytes into a new Python `bytes` object.
+    pub fn to_pybytes(&self, py: Python<'_>) -> Py<PyBytes> {
+        PyBytes::new(py, &self.bytes).unbind()
+    }
+}
+
+/// A Python class wrapping an immutable `UntypedBytes`, exposed as `UntypedBytes`.
+///
+/// It supports `len()` and the read-only buffer protocol, so for example
+/// `numpy.frombuffer(buf, dtype=...)` views the bytes without copying.
+#[pyclass(frozen, name = "UntypedBytes")]
+pub struct PyUntypedBytes {
+    bytes: UntypedBytes,
+}
+
+impl PyUntypedBytes {
+    pub fn new(bytes: UntypedBytes) -> Self {
+        PyUntypedBytes { bytes }
+    }
+
+    pub fn bytes(&self) -> &UntypedBytes {
+        &self.bytes
+    }
+}
+
+#[pymethods]
+impl PyUntypedBytes {
+    #[new]
+    fn py_new(obj: &Bound<'_, PyAny>) -> PyResult<Self> {
+        UntypedBytes::from_pybuffer(obj).map(Self::new)
+    }
+
+    fn __len__(&self) -> usize {
+        self.bytes.len()
+    }
+
+    fn to_bytes(&self, py: Python<'_>) -> Py<PyBytes> {
+        self.bytes.to_pybytes(py)
+    }
+
+    unsafe fn __getbuffer__(
+        slf: Bound<'_, Self>,
+        view: *mut ffi::Py_buffer,
+        flags: c_int,
+    ) -> PyResult<()> {
+        if view.is_null() {
+            return Err(PyBufferError::new_err("view is null"));
+        }
+        if (flags & ffi::PyBUF_WRITABLE) == ffi::PyBUF_WRITABLE {
+            return Err(PyBufferError::new_err("`UntypedBytes` is read-only"));
+        }
+
+        // The class is frozen, so the bytes stay put for as long as `view.obj` keeps it alive.
+        let bytes = &slf.get().bytes.bytes;
+        (*view).buf = bytes.as_ptr() as *mut c_void;
+        (*view).len = bytes.len() as isize;
+        (*view).obj = slf.into_any().into_ptr();
+        (*view).readonly = 1;
+        (*view).itemsize = 1;
+        (*view).format = if (flags & ffi::PyBUF_FORMAT) == ffi::PyBUF_FORMAT {
+            b"B\0".as_ptr() as *mut c_char
+        } else {
+            ptr::null_mut()
+        };
+        (*view).ndim = 1;
+        (*view).shape = if (flags & ffi::PyBUF_ND) == ffi::PyBUF_ND {
+            &mut (*view).len
+        } else {
+            ptr::null_mut()
+        };
+        (*view).strides = if (flags & ffi::PyBUF_STRIDES) == ffi::PyBUF_STRIDES {
+            &mut (*view).itemsize
+        } else {
+            ptr::null_mut()
+        };
+        (*view).suboffsets = ptr::null_mut();
+        (*view).internal = ptr::null_mut();
+        Ok(())
+    }
+
+    unsafe fn __releasebuffer__(&self, _view: *mut ffi::Py_buffer) {}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use pyo3::types::{PyByteArray, PySlice};
+
+    fn memoryview<'py>(py: Python<'py>, obj: &Bound<'py, PyAny>) -> Bound<'py, PyAny> {
+        py.import("builtins")
+            .unwrap()
+            .getattr("memoryview")
+            .unwrap()
+            .call1((obj,))
+            .unwrap()
+    }
+
+    // A numpy-style array of native-endian `f32`s, built on the Python side.
+    fn float_array<'py>(py: Python<'py>) -> Bound<'py, PyAny> {
+        py.import("array")
+            .unwrap()
+            .getattr("array")
+            .unwrap()
+            .call1(("f", vec![1.0f32, -2.5, 0.0, 1e-3]))
+            .unwrap()
+    }
+
+    #[test]
+    fn round_trip_through_python() {
+        Python::initialize();
+        Python::attach(|py| {
+            let bytes = UntypedBytes::from_pybuffer(&float_array(py)).unwrap();
+            assert_eq!(
+                unsafe { bytes.to_vec_of::<f32, _>(..) },
+                [1.0, -2.5, 0.0, 1e-3]
+            );
+
+            let exported = bytes.to_pybytes(py);
+            assert_eq!(exported.bind(py).as_bytes(), &bytes.bytes[..]);
+
+            let empty = PyByteArray::new(py, &[]);
+            assert!(UntypedBytes::from_pybuffer(&empty).unwrap().is_empty());
+        })
+    }
+
+    #[test]
+    fn non_contiguous_buffers_are_rejected() {
+        Python::initialize();
+        Python::attach(|py| {
+            let data = PyByteArray::new(py, &[0, 1, 2, 3]);
+            let strided = memoryview(py, data.as_any())
+                .get_item(PySlice::new(py, 0, 4, 2))
+                .unwrap();
+            assert!(UntypedBytes::from_pybuffer(&strided).is_err());
+        })
+    }
+
+    // `memoryview` goes through `__getbuffer__`, just as `numpy.frombuffer` does.
+    #[test]
+    fn class_exposes_read_only_buffer() {
+        Python::initialize();
+        Python::attach(|py| {
+            let wrapped =
+                Bound::new(py, PyUntypedBytes::py_new(&float_array(py)).unwrap()).unwrap();
+            assert_eq!(wrapped.len().unwrap(), 16);
+            let view = memoryview(py, wrapped.as_any());
+            assert!(view.getattr("readonly").unwrap().extract::<bool>().unwrap());
+            let copied: Vec<u8> = view.call_method0("tobytes").unwrap().extract().unwrap();
+            assert_eq!(copied, wrapped.get().bytes().bytes);
+            let floats = view.call_method1("cast", ("f",)).unwrap();
+            assert_eq!(floats.get_item(1).unwrap().extract::<f32>().unwrap(), -2.5);
+        })
+    }
+}
@@ -0,0 +1,223 @@
+use crate::{UntypedBytes, UntypedSlice};
+use core::{borrow::Borrow, mem};
+
+/// A first-in first-out queue of untyped bytes: values are pushed at the back and popped from
+/// the front.
+///
+/// The queued bytes are always contiguous. Popping only advances a head offset, and the consumed
+/// prefix is reclaimed by shifting the queued bytes down once it is at least as large as what
+/// remains, so both ends are amortized O(1).
+#[derive(Clone, Debug, Default)]
+pub struct UntypedQueue {
+    bytes: UntypedBytes,
+    head: usize,
+}
+
+impl UntypedQueue {
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    pub fn with_capacity(capacity: usize) -> Self {
+        UntypedQueue {
+            bytes: UntypedBytes::with_capacity(capacity),
+            head: 0,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len_bytes() == 0
+    }
+
+    /// The number of queued bytes.
+    pub fn len_bytes(&self) -> usize {
+        self.bytes.len() - self.head
+    }
+
+    pub fn clear(&mut self) {
+        self.bytes.clear();
+        self.head = 0;
+    }
+
+    pub fn push_back<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        self.compact();
+        self.bytes.push(value)
+    }
+
+    pub fn extend_back_from_slice<T, V>(&mut self, value: V)
+    where
+        T: Copy + Send + Sync + 'static,
+        V: Borrow<[T]>,
+    {
+        self.compact();
+        self.bytes.extend_from_slice(value)
+    }
+
+    /// Reads the `T` at the front of the queue without removing it, or returns `None` if fewer
+    /// than `size_of::<T>()` bytes are queued. This is only safe if those bytes were pushed as a
+    /// value of type `T`.
+    pub unsafe fn front<T: Copy + Send + Sync + 'static>(&self) -> Option<T> {
+        if self.len_bytes() < mem::size_of::<T>() {
+            return None;
+        }
+        Some(self.as_view().read_at(0))
+    }
+
+    /// Like `front`, but removes the value from the queue.
+    pub unsafe fn pop_front<T: Copy + Send + Sync + 'static>(&mut self) -> Option<T> {
+        let value = self.front()?;
+        self.advance(mem::size_of::<T>());
+        Some(value)
+    }
+
+    /// Removes up to `max_bytes` from the front of the queue, returning them in a new buffer. The
+    /// split is bytewise, so a `max_bytes` that is not a multiple of the element size splits an
+    /// element across batches.
+    pub fn drain_front(&mut self, max_bytes: usize) -> UntypedBytes {
+        let len = max_bytes.min(self.len_bytes());
+        let result = self.as_view().slice(..len).to_owned();
+        self.advance(len);
+        result
+    }
+
+    /// Borrows the queued bytes, oldest first.
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        self.bytes.as_view().slice(self.head..)
+    }
+
+    fn advance(&mut self, len: usize) {
+        self.head += len;
+        if self.head == self.bytes.len() {
+            self.clear()
+        }
+    }
+
+    // Reclaims the consumed prefix once it is at least as large as the queued bytes, so the bytes
+    // moved are paid for by the bytes popped since the last compaction.
+    fn compact(&mut self) {
+        if self.head > 0 && self.head >= self.len_bytes() {
+            let len = self.len_bytes();
+            self.bytes.bytes.copy_within(self.head.., 0);
+            self.bytes.truncate_bytes(len);
+            self.head = 0;
+        }
+    }
+}
+
+impl From<UntypedBytes> for UntypedQueue {
+    fn from(bytes: UntypedBytes) -> Self {
+        UntypedQueue { bytes, head: 0 }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::collections::VecDeque;
+
+    #[derive(Clone, Copy, Debug, PartialEq)]
+    enum Record {
+        Small(u16),
+        Large(u64),
+        Odd([u8; 3]),
+    }
+
+    fn push(queue: &mut UntypedQueue, record: Record) {
+        match record {
+            Record::Small(v) => queue.push_back(v),
+            Record::Large(v) => queue.push_back(v),
+            Record::Odd(v) => queue.extend_back_from_slice(v),
+        }
+    }
+
+    // Pops a value of the same type as the front of the model.
+    fn pop(queue: &mut UntypedQueue, like: Record) -> Option<Record> {
+        unsafe {
+            match like {
+                Record::Small(_) => queue.pop_front().map(Record::Small),
+                Record::Large(_) => queue.pop_front().map(Record::Large),
+                Record::Odd(_) => queue.pop_front().map(Record::Odd),
+            }
+        }
+    }
+
+    fn record_len(record: Record) -> usize {
+        match record {
+            Record::Small(_) => 2,
+            Record::Large(_) => 8,
+            Record::Odd(_) => 3,
+        }
+    }
+
+    #[test]
+    fn matches_reference_model() {
+        let mut queue = UntypedQueue::new();
+        let mut model = VecDeque::new();
+        let mut state = 0x2545_f491u32;
+        for step in 0..5000u32 {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            // Push slightly more often than pop, so the queue grows and compacts along the way.
+            if state % 9 < 5 {
+                let record = match state % 3 {
+                    0 => Record::Small(step as u16),
+                    1 => Record::Large(u64::from(step) << 32 | u64::from(state)),
+                    _ => Record::Odd([step as u8, (step >> 8) as u8, state as u8]),
+                };
+                push(&mut queue, record);
+                model.push_back(record);
+                // The consumed prefix never outgrows the queued bytes after a push.
+                assert!(queue.head < queue.len_bytes());
+            } else if let Some(&front) = model.front() {
+                assert_eq!(pop(&mut queue, front), model.pop_front());
+            } else {
+                assert_eq!(unsafe { queue.pop_front::<u8>() }, None);
+            }
+            let len: usize = model.iter().map(|&r| record_len(r)).sum();
+            assert_eq!(queue.len_bytes(), len);
+        }
+        while let Some(&front) = model.front() {
+            assert_eq!(pop(&mut queue, front), model.pop_front());
+        }
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn front_peeks() {
+        let mut queue = UntypedQueue::new();
+        assert_eq!(unsafe { queue.front::<u32>() }, None);
+        queue.push_back(7u32);
+        queue.push_back(8u16);
+        assert_eq!(unsafe { queue.front::<u32>() }, Some(7));
+        assert_eq!(unsafe { queue.pop_front::<u32>() }, Some(7));
+        // Too few bytes are left for a `u32`.
+        assert_eq!(unsafe { queue.front::<u32>() }, None);
+        assert_eq!(unsafe { queue.pop_front::<u32>() }, None);
+        assert_eq!(unsafe { queue.pop_front::<u16>() }, Some(8));
+        assert!(queue.is_empty());
+    }
+
+    #[test]
+    fn drain_front_batches() {
+        let mut queue = UntypedQueue::from(UntypedBytes::from_slice([1u32, 2, 3, 4, 5]));
+        let batch = queue.drain_front(8);
+        assert_eq!(unsafe { batch.to_vec_of::<u32, _>(..) }, [1, 2]);
+        assert_eq!(queue.len_bytes(), 12);
+        queue.push_back(6u32);
+        let rest = queue.drain_front(usize::MAX);
+        assert_eq!(unsafe { rest.to_vec_of::<u32, _>(..) }, [3, 4, 5, 6]);
+        assert!(queue.is_empty());
+        assert!(queue.drain_front(10).is_empty());
+    }
+
+    #[test]
+    fn view_is_oldest_first() {
+        let mut queue = UntypedQueue::with_capacity(16);
+        queue.extend_back_from_slice([1u8, 2, 3, 4]);
+        unsafe { queue.pop_front::<u8>() };
+        assert_eq!(unsafe { queue.as_view().as_slice() }, &[2, 3, 4]);
+        queue.clear();
+        assert!(queue.as_view().is_empty());
+    }
+}
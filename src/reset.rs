@@ -0,0 +1,158 @@
+use crate::UntypedBytes;
+
+/// Controls how `UntypedBytes::reset_with_hysteresis` sizes a buffer that is cleared and refilled
+/// every frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub struct ResetPolicy {
+    /// Each reset, the high-water mark falls by `1 / decay` of itself unless the length just used
+    /// was higher, so the mark tracks the recent peak and forgets old spikes. Zero disables the
+    /// decay.
+    pub decay: usize,
+    /// The capacity counts as oversized once it exceeds the high-water mark by this factor.
+    pub shrink_factor: usize,
+    /// The number of consecutive oversized resets after which the capacity is shrunk to the
+    /// high-water mark.
+    pub shrink_after: u32,
+}
+
+impl Default for ResetPolicy {
+    fn default() -> Self {
+        ResetPolicy {
+            decay: 16,
+            shrink_factor: 2,
+            shrink_after: 8,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct ResetState {
+    high_water: usize,
+    oversized_resets: u32,
+}
+
+impl UntypedBytes {
+    /// Clears the buffer for reuse in the next frame, adapting the capacity to recent frames.
+    ///
+    /// The length just used updates a decaying high-water mark. Capacity is pre-reserved up to
+    /// the mark, and shrunk back down to it after the capacity has exceeded the mark by
+    /// `shrink_factor` for `shrink_after` consecutive resets, so a one-off spike does not pin its
+    /// allocation forever and a brief lull does not give up capacity that will be needed again.
+    pub fn reset_with_hysteresis(&mut self, policy: ResetPolicy) {
+        assert!(
+            policy.shrink_factor >= 1,
+            "`ResetPolicy` requires a shrink factor of at least 1"
+        );
+        let state = &mut self.reset;
+        let decayed = match policy.decay {
+            0 => state.high_water,
+            decay => state.high_water - state.high_water / decay,
+        };
+        state.high_water = decayed.max(self.bytes.len());
+        let high_water = state.high_water;
+
+        self.clear();
+        if self.capacity() / policy.shrink_factor > high_water {
+            self.reset.oversized_resets += 1;
+            if self.reset.oversized_resets >= policy.shrink_after {
+                self.bytes.shrink_to(high_water);
+                self.reset.oversized_resets = 0;
+            }
+        } else {
+            self.reset.oversized_resets = 0;
+        }
+        self.reserve(high_water)
+    }
+
+    /// The decaying high-water mark of the lengths seen by `reset_with_hysteresis`, in bytes.
+    pub fn high_water_mark(&self) -> usize {
+        self.reset.high_water
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+
+    // Simulates a frame that writes `len` bytes, then resets the buffer for the next one.
+    fn frame(bytes: &mut UntypedBytes, len: usize, policy: ResetPolicy) {
+        bytes.extend_from_slice(&vec![0u8; len][..]);
+        bytes.reset_with_hysteresis(policy);
+        assert!(bytes.is_empty());
+        assert!(bytes.capacity() >= bytes.high_water_mark());
+    }
+
+    #[test]
+    fn spike_then_quiet_converges() {
+        let policy = ResetPolicy::default();
+        let mut bytes = UntypedBytes::new();
+        for _ in 0..20 {
+            frame(&mut bytes, 1000, policy);
+        }
+        frame(&mut bytes, 1 << 20, policy);
+        assert_eq!(bytes.high_water_mark(), 1 << 20);
+        // The spike's allocation survives the first few quiet frames.
+        for _ in 0..policy.shrink_after {
+            frame(&mut bytes, 1000, policy);
+            assert!(bytes.capacity() >= 1 << 20);
+        }
+        for _ in 0..300 {
+            frame(&mut bytes, 1000, policy);
+        }
+        assert_eq!(bytes.high_water_mark(), 1000);
+        assert!(bytes.capacity() <= 2 * 1000, "{}", bytes.capacity());
+    }
+
+    #[test]
+    fn brief_lull_keeps_capacity() {
+        let policy = ResetPolicy::default();
+        let mut bytes = UntypedBytes::new();
+        for _ in 0..20 {
+            frame(&mut bytes, 4096, policy);
+        }
+        let capacity = bytes.capacity();
+        for _ in 0..policy.shrink_after - 1 {
+            frame(&mut bytes, 0, policy);
+        }
+        assert_eq!(bytes.capacity(), capacity);
+        frame(&mut bytes, 4096, policy);
+        assert_eq!(bytes.capacity(), capacity);
+        assert_eq!(bytes.high_water_mark(), 4096);
+    }
+
+    #[test]
+    fn high_water_mark_decays() {
+        let policy = ResetPolicy {
+            decay: 4,
+            ..Default::default()
+        };
+        let mut bytes = UntypedBytes::new();
+        assert_eq!(bytes.high_water_mark(), 0);
+        frame(&mut bytes, 1000, policy);
+        assert_eq!(bytes.high_water_mark(), 1000);
+        frame(&mut bytes, 0, policy);
+        assert_eq!(bytes.high_water_mark(), 750);
+        // A longer frame raises the mark straight away.
+        frame(&mut bytes, 800, policy);
+        assert_eq!(bytes.high_water_mark(), 800);
+
+        let policy = ResetPolicy {
+            decay: 0,
+            ..Default::default()
+        };
+        for _ in 0..10 {
+            frame(&mut bytes, 0, policy);
+        }
+        assert_eq!(bytes.high_water_mark(), 800);
+    }
+
+    #[test]
+    #[should_panic(expected = "`ResetPolicy` requires a shrink factor of at least 1")]
+    fn zero_shrink_factor_panics() {
+        UntypedBytes::new().reset_with_hysteresis(ResetPolicy {
+            shrink_factor: 0,
+            ..Default::default()
+        });
+    }
+}
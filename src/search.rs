@@ -0,0 +1,231 @@
+// Substring search over byte slices. With the `memchr` feature this defers to `memchr`,
+// otherwise it uses the two-way algorithm (Crochemore-Perrin), which needs no allocation and is
+// linear in the length of the haystack.
+
+#[cfg(feature = "memchr")]
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(0),
+        [byte] => memchr::memchr(*byte, haystack),
+        _ => memchr::memmem::find(haystack, needle),
+    }
+}
+
+#[cfg(feature = "memchr")]
+pub(crate) fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(haystack.len()),
+        [byte] => memchr::memrchr(*byte, haystack),
+        _ => memchr::memmem::rfind(haystack, needle),
+    }
+}
+
+#[cfg(not(feature = "memchr"))]
+pub(crate) fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    match needle {
+        [] => Some(0),
+        [byte] => haystack.iter().position(|b| b == byte),
+        _ => two_way(haystack.len(), needle.len(), |i| haystack[i], |i| needle[i]),
+    }
+}
+
+// Searches the reversed haystack for the reversed needle.
+#[cfg(not(feature = "memchr"))]
+pub(crate) fn rfind(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    let (len, n) = (haystack.len(), needle.len());
+    match needle {
+        [] => Some(len),
+        [byte] => haystack.iter().rposition(|b| b == byte),
+        _ => two_way(len, n, |i| haystack[len - 1 - i], |i| needle[n - 1 - i])
+            .map(|position| len - position - n),
+    }
+}
+
+// Returns `(left, period)` of the maximal suffix of `needle` under the byte order, or the reverse
+// byte order when `reversed` is set.
+#[cfg(not(feature = "memchr"))]
+fn maximal_suffix(n: usize, needle: &impl Fn(usize) -> u8, reversed: bool) -> (usize, usize) {
+    let mut left = 0;
+    let mut right = 1;
+    let mut offset = 0;
+    let mut period = 1;
+    while right + offset < n {
+        let a = needle(right + offset);
+        let b = needle(left + offset);
+        if (a < b && !reversed) || (a > b && reversed) {
+            right += offset + 1;
+            offset = 0;
+            period = right - left;
+        } else if a == b {
+            if offset + 1 == period {
+                right += offset + 1;
+                offset = 0;
+            } else {
+                offset += 1;
+            }
+        } else {
+            left = right;
+            right += 1;
+            offset = 0;
+            period = 1;
+        }
+    }
+    (left, period)
+}
+
+// Two-way search over index accessors, so the same code can run over reversed slices.
+#[cfg(not(feature = "memchr"))]
+fn two_way(
+    haystack_len: usize,
+    n: usize,
+    haystack: impl Fn(usize) -> u8,
+    needle: impl Fn(usize) -> u8,
+) -> Option<usize> {
+    if n > haystack_len {
+        return None;
+    }
+
+    let (crit_pos, period) = {
+        let (left_false, period_false) = maximal_suffix(n, &needle, false);
+        let (left_true, period_true) = maximal_suffix(n, &needle, true);
+        if left_false > left_true {
+            (left_false, period_false)
+        } else {
+            (left_true, period_true)
+        }
+    };
+
+    // If the prefix before the critical position repeats with `period`, the needle is periodic
+    // and we can remember how much of it already matched. Otherwise use the long period variant.
+    let periodic = crit_pos + period <= n && (0..crit_pos).all(|i| needle(i) == needle(i + period));
+    let period = if periodic {
+        period
+    } else {
+        crit_pos.max(n - crit_pos) + 1
+    };
+
+    let mut position = 0;
+    let mut memory = 0;
+    'search: while position + n <= haystack_len {
+        let start = if periodic {
+            crit_pos.max(memory)
+        } else {
+            crit_pos
+        };
+        for i in start..n {
+            if needle(i) != haystack(position + i) {
+                position += i - crit_pos + 1;
+                memory = 0;
+                continue 'search;
+            }
+        }
+
+        let start = if periodic { memory } else { 0 };
+        for i in (start..crit_pos).rev() {
+            if needle(i) != haystack(position + i) {
+                position += period;
+                if periodic {
+                    memory = n - period;
+                }
+                continue 'search;
+            }
+        }
+
+        return Some(position);
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UntypedBytes;
+    use alloc::vec::Vec;
+
+    #[test]
+    fn finds_multi_byte_needle_in_the_middle() {
+        let bytes = UntypedBytes::from_slice(*b"header\r\n\r\nbody\r\n");
+        assert_eq!(bytes.find(b"\r\n\r\n"), Some(6));
+        assert_eq!(bytes.find(b"body"), Some(10));
+        assert_eq!(bytes.find(b"\n"), Some(7));
+        assert_eq!(bytes.find(b""), Some(0));
+    }
+
+    #[test]
+    fn missing_needle_is_not_found() {
+        let bytes = UntypedBytes::from_slice(*b"abcabcabd");
+        assert_eq!(bytes.find(b"abd"), Some(6));
+        assert_eq!(bytes.find(b"abe"), None);
+        assert_eq!(bytes.find(b"z"), None);
+        assert_eq!(bytes.find(b"abcabcabdx"), None);
+        assert_eq!(UntypedBytes::new().find(b"a"), None);
+    }
+
+    // Checks every needle drawn from a small alphabet against a naive scan, which exercises both
+    // the periodic and the long period paths of the two-way search.
+    #[test]
+    fn find_matches_naive_search() {
+        let haystack = b"aabaabaaabbabaabaaabababbbaaab";
+        for len in 2..6 {
+            for bits in 0..1u32 << len {
+                let needle: Vec<u8> = (0..len).map(|i| b'a' + (bits >> i & 1) as u8).collect();
+                let naive = haystack.windows(len).position(|w| w == &needle[..]);
+                assert_eq!(find(haystack, &needle), naive, "{:?}", needle);
+            }
+        }
+    }
+
+    #[test]
+    fn rfind_placements() {
+        let bytes = UntypedBytes::from_slice(*b"SIGxxSIGyySIG");
+        assert_eq!(bytes.rfind(b"SIG"), Some(10));
+        assert_eq!(bytes.find(b"SIG"), Some(0));
+        assert_eq!(bytes.rfind(b"xxS"), Some(3));
+        assert_eq!(bytes.rfind(b"y"), Some(9));
+        assert_eq!(bytes.rfind(b"SIGz"), None);
+        assert_eq!(bytes.rfind(b""), Some(13));
+        assert_eq!(UntypedBytes::new().rfind(b""), Some(0));
+        assert_eq!(UntypedBytes::new().rfind(b"a"), None);
+    }
+
+    #[test]
+    fn rfind_matches_naive_search() {
+        let haystack = b"aabaabaaabbabaabaaabababbbaaab";
+        for len in 2..6 {
+            for bits in 0..1u32 << len {
+                let needle: Vec<u8> = (0..len).map(|i| b'a' + (bits >> i & 1) as u8).collect();
+                let naive = haystack.windows(len).rposition(|w| w == &needle[..]);
+                assert_eq!(rfind(haystack, &needle), naive, "{:?}", needle);
+            }
+        }
+    }
+
+    #[test]
+    fn find_iter_repeated_occurrences() {
+        let bytes =
+            UntypedBytes::from_slice(*b"\xde\xad\xbe\xef--\xde\xad\xbe\xef\xde\xad\xbe\xef");
+        let found: Vec<usize> = bytes.find_iter(b"\xde\xad\xbe\xef").collect();
+        assert_eq!(found, [0, 6, 10]);
+        let none: Vec<usize> = bytes.find_iter(b"\xca\xfe").collect();
+        assert!(none.is_empty());
+    }
+
+    // Matches do not overlap, like `str::match_indices`.
+    #[test]
+    fn find_iter_is_non_overlapping() {
+        let bytes = UntypedBytes::from_slice(*b"aaaaa");
+        let found: Vec<usize> = bytes.find_iter(b"aa").collect();
+        assert_eq!(found, [0, 2]);
+        let expected: Vec<usize> = "aaaaa".match_indices("aa").map(|(i, _)| i).collect();
+        assert_eq!(found, expected);
+    }
+
+    #[test]
+    fn find_iter_empty_needle() {
+        let bytes = UntypedBytes::from_slice(*b"abc");
+        let found: Vec<usize> = bytes.find_iter(b"").collect();
+        assert_eq!(found, [0, 1, 2, 3]);
+        let empty: Vec<usize> = UntypedBytes::new().find_iter(b"").collect();
+        assert_eq!(empty, [0]);
+    }
+}
@@ -0,0 +1,175 @@
+use crate::{UntypedBytes, UntypedSlice};
+use alloc::sync::Arc;
+use core::ops::Range;
+
+/// An immutable, reference counted view into frozen untyped bytes, created by
+/// `UntypedBytes::into_shared`.
+///
+/// Cloning and splitting only adjust the reference count and the viewed range, the bytes
+/// themselves are never copied. The backing allocation is freed when the last view is dropped.
+#[derive(Clone, Debug)]
+pub struct SharedUntypedBytes {
+    bytes: Arc<[u8]>,
+    range: Range<usize>,
+}
+
+impl UntypedBytes {
+    /// Freezes the buffer into a `SharedUntypedBytes` viewing all of it.
+    pub fn into_shared(self) -> SharedUntypedBytes {
+        SharedUntypedBytes::from(self.freeze())
+    }
+}
+
+impl SharedUntypedBytes {
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        UntypedSlice::new(&self.bytes[self.range.clone()])
+    }
+
+    /// See `UntypedBytes::as_slice`.
+    pub unsafe fn as_slice(&self) -> &[u8] {
+        &self.bytes[self.range.clone()]
+    }
+
+    /// See `UntypedSlice::read_at`.
+    pub unsafe fn read_at<T: Copy + Send + Sync + 'static>(&self, offset: usize) -> T {
+        self.as_view().read_at(offset)
+    }
+
+    /// Splits off and returns the bytes before `at`, leaving `self` with the bytes from `at`
+    /// onward. Panics if `at` is greater than the length.
+    pub fn split_to(&mut self, at: usize) -> SharedUntypedBytes {
+        let mid = self.split_point(at);
+        let front = SharedUntypedBytes {
+            bytes: self.bytes.clone(),
+            range: self.range.start..mid,
+        };
+        self.range.start = mid;
+        front
+    }
+
+    /// Splits off and returns the bytes from `at` onward, leaving `self` with the bytes before
+    /// `at`. Panics if `at` is greater than the length.
+    pub fn split_off(&mut self, at: usize) -> SharedUntypedBytes {
+        let mid = self.split_point(at);
+        let back = SharedUntypedBytes {
+            bytes: self.bytes.clone(),
+            range: mid..self.range.end,
+        };
+        self.range.end = mid;
+        back
+    }
+
+    fn split_point(&self, at: usize) -> usize {
+        assert!(
+            at <= self.len(),
+            "split point {} is out of bounds for {} bytes",
+            at,
+            self.len()
+        );
+        self.range.start + at
+    }
+}
+
+impl From<Arc<[u8]>> for SharedUntypedBytes {
+    fn from(bytes: Arc<[u8]>) -> Self {
+        SharedUntypedBytes {
+            range: 0..bytes.len(),
+            bytes,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::{sync::Weak, vec::Vec};
+
+    // Ten 12-byte messages, each three `u32`s tagged with the message index.
+    fn messages() -> UntypedBytes {
+        let mut bytes = UntypedBytes::new();
+        for i in 0..10u32 {
+            bytes.push([i, i * 100, !i]);
+        }
+        bytes
+    }
+
+    // Splits the whole buffer into messages, returning them along with a weak reference that is
+    // used to watch the backing allocation.
+    fn split_messages() -> (Vec<SharedUntypedBytes>, Weak<[u8]>) {
+        let frozen = messages().freeze();
+        let weak = Arc::downgrade(&frozen);
+        let mut rest = SharedUntypedBytes::from(frozen);
+        let mut pieces = Vec::new();
+        while !rest.is_empty() {
+            pieces.push(rest.split_to(12));
+        }
+        assert_eq!(Arc::strong_count(&weak.upgrade().unwrap()), 12);
+        drop(rest);
+        (pieces, weak)
+    }
+
+    #[test]
+    fn split_into_pieces() {
+        let (pieces, _) = split_messages();
+        assert_eq!(pieces.len(), 10);
+        let base = unsafe { pieces[0].as_slice() }.as_ptr();
+        for (i, piece) in pieces.iter().enumerate() {
+            assert_eq!(piece.len(), 12);
+            // Every piece points into the same allocation.
+            assert_eq!(
+                unsafe { piece.as_slice() }.as_ptr(),
+                base.wrapping_add(i * 12)
+            );
+            let i = i as u32;
+            assert_eq!(unsafe { piece.read_at::<[u32; 3]>(0) }, [i, i * 100, !i]);
+        }
+    }
+
+    // Run under Miri to check the allocation is freed exactly once.
+    #[test]
+    fn dropped_in_random_order() {
+        let (mut pieces, weak) = split_messages();
+        let mut state = 0x2545_f491u32;
+        while !pieces.is_empty() {
+            assert!(weak.upgrade().is_some());
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            let piece = pieces.swap_remove(state as usize % pieces.len());
+            let tag = unsafe { piece.read_at::<u32>(0) };
+            assert_eq!(unsafe { piece.read_at::<u32>(8) }, !tag);
+        }
+        assert_eq!(weak.strong_count(), 0);
+        assert!(weak.upgrade().is_none());
+    }
+
+    #[test]
+    fn split_off_and_split_further() {
+        let mut front = messages().into_shared();
+        let mut back = front.split_off(60);
+        assert_eq!((front.len(), back.len()), (60, 60));
+        let tail = back.split_off(48);
+        let middle = back.split_to(12);
+        assert_eq!(unsafe { middle.read_at::<u32>(0) }, 5);
+        assert_eq!(unsafe { back.read_at::<u32>(0) }, 6);
+        assert_eq!(unsafe { tail.read_at::<u32>(0) }, 9);
+        assert_eq!(unsafe { front.split_to(0).as_slice() }, &[] as &[u8]);
+        assert_eq!(front.split_off(60).len(), 0);
+        assert_eq!(unsafe { front.read_at::<u32>(48) }, 4);
+    }
+
+    #[test]
+    #[should_panic(expected = "split point 13 is out of bounds for 12 bytes")]
+    fn split_past_the_end_panics() {
+        let (mut pieces, _) = split_messages();
+        pieces[0].split_to(13);
+    }
+}
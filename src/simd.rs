@@ -0,0 +1,99 @@
+// Chunked equality. Each block is compared as `u64` lanes folded together with xor and or, which
+// the compiler vectorizes on stable without `std::simd`.
+
+use crate::UntypedBytes;
+use core::convert::TryInto;
+
+const LANES: usize = 32;
+const BLOCK: usize = LANES * 8;
+
+impl UntypedBytes {
+    /// Returns `true` if the backing bytes equal `other`, comparing a block of bytes at a time.
+    /// Intended for megabyte scale buffers.
+    ///
+    /// Comparisons that large are limited by memory bandwidth, so on x86_64 the `fast_eq` bench
+    /// measures this about even with slice equality, which is already a vectorized `memcmp`, and
+    /// somewhat slower on small buffers.
+    ///
+    /// This is a bitwise comparison that returns as soon as a block differs, so it is not
+    /// constant-time.
+    pub fn fast_eq(&self, other: &[u8]) -> bool {
+        if self.len() != other.len() {
+            return false;
+        }
+        let mut ours = self.bytes.chunks_exact(BLOCK);
+        let mut theirs = other.chunks_exact(BLOCK);
+        for (a, b) in (&mut ours).zip(&mut theirs) {
+            let mut diff = 0;
+            for lane in 0..LANES {
+                diff |= read_lane(a, lane) ^ read_lane(b, lane);
+            }
+            if diff != 0 {
+                return false;
+            }
+        }
+        ours.remainder() == theirs.remainder()
+    }
+}
+
+#[inline(always)]
+fn read_lane(block: &[u8], lane: usize) -> u64 {
+    u64::from_ne_bytes(block[lane * 8..][..8].try_into().unwrap())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec::Vec;
+
+    fn data(len: usize) -> Vec<u8> {
+        (0..len).map(|i| (i * 7 + 3) as u8).collect()
+    }
+
+    // Lengths on either side of the block size, with and without a trailing partial block.
+    const LENGTHS: [usize; 9] = [0, 1, 7, 8, 63, 64, 65, 200, 4096];
+
+    #[test]
+    fn equal_buffers() {
+        for &len in &LENGTHS {
+            let bytes = UntypedBytes::from_slice(&data(len)[..]);
+            assert!(bytes.fast_eq(&data(len)), "{} bytes", len);
+        }
+    }
+
+    #[test]
+    fn every_flipped_byte_is_found() {
+        for &len in &LENGTHS[1..8] {
+            let bytes = UntypedBytes::from_slice(&data(len)[..]);
+            for i in 0..len {
+                let mut other = data(len);
+                other[i] ^= 0x10;
+                assert!(!bytes.fast_eq(&other), "byte {} of {}", i, len);
+            }
+        }
+    }
+
+    #[test]
+    fn unaligned_other() {
+        // Compare against slices starting at every offset within a `u64`.
+        let bytes = UntypedBytes::from_slice(&data(200)[..]);
+        let mut backing = Vec::with_capacity(208);
+        for offset in 0..8 {
+            backing.clear();
+            backing.resize(offset, 0xff);
+            backing.extend_from_slice(&data(200));
+            assert!(bytes.fast_eq(&backing[offset..]), "offset {}", offset);
+            backing[offset + 150] ^= 1;
+            assert!(!bytes.fast_eq(&backing[offset..]), "offset {}", offset);
+        }
+    }
+
+    #[test]
+    fn different_lengths() {
+        let bytes = UntypedBytes::from_slice(&data(64)[..]);
+        assert!(!bytes.fast_eq(&data(63)));
+        assert!(!bytes.fast_eq(&data(65)));
+        assert!(!UntypedBytes::new().fast_eq(&data(1)));
+        assert!(UntypedBytes::new().fast_eq(&[]));
+    }
+}
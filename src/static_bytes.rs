@@ -0,0 +1,91 @@
+use crate::UntypedSlice;
+
+/// Untyped bytes that can be built in a const context, so a `static` can hold baked data with no
+/// runtime initialization. Use `as_view` to treat it uniformly with heap buffers.
+#[derive(Clone, Copy, Debug)]
+pub struct StaticUntypedBytes<const N: usize> {
+    bytes: [u8; N],
+}
+
+impl<const N: usize> StaticUntypedBytes<N> {
+    pub const fn from_array(bytes: [u8; N]) -> Self {
+        StaticUntypedBytes { bytes }
+    }
+
+    pub const fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    pub const fn len(&self) -> usize {
+        N
+    }
+
+    pub const fn as_ptr(&self) -> *const u8 {
+        self.bytes.as_ptr()
+    }
+
+    pub fn as_view(&self) -> UntypedSlice<'_> {
+        UntypedSlice::new(&self.bytes)
+    }
+
+    /// See `UntypedBytes::cast`.
+    pub unsafe fn cast<T: Copy + Send + Sync + 'static>(&self) -> T {
+        self.as_view().cast()
+    }
+}
+
+impl<'a, const N: usize> From<&'a StaticUntypedBytes<N>> for UntypedSlice<'a> {
+    fn from(value: &'a StaticUntypedBytes<N>) -> Self {
+        value.as_view()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UntypedBytes;
+
+    // Three RGBA colors baked as native endian `u32`s, entirely in a const context.
+    const fn bake_palette(colors: [u32; 3]) -> [u8; 12] {
+        let mut bytes = [0; 12];
+        let mut i = 0;
+        while i < 12 {
+            bytes[i] = colors[i / 4].to_ne_bytes()[i % 4];
+            i += 1;
+        }
+        bytes
+    }
+
+    static PALETTE: StaticUntypedBytes<12> =
+        StaticUntypedBytes::from_array(bake_palette([0xff00_00ff, 0x00ff_00ff, 0x0000_ffff]));
+
+    const PALETTE_LEN: usize = PALETTE.len();
+
+    #[test]
+    fn static_read_at() {
+        assert_eq!(PALETTE_LEN, 12);
+        assert!(!PALETTE.is_empty());
+        assert_eq!(unsafe { PALETTE.as_view().read_at::<u32>(4) }, 0x00ff_00ff);
+        assert_eq!(
+            unsafe { PALETTE.cast::<[u32; 3]>() },
+            [0xff00_00ff, 0x00ff_00ff, 0x0000_ffff]
+        );
+    }
+
+    // Heap and static buffers can be handled uniformly through `UntypedSlice`.
+    fn last_color<'a>(view: impl Into<UntypedSlice<'a>>) -> u32 {
+        let view = view.into();
+        unsafe { view.read_at(view.len() - 4) }
+    }
+
+    #[test]
+    fn uniform_with_heap_buffers() {
+        let heap = UntypedBytes::from_slice([0xff00_00ffu32, 0x00ff_00ff, 0x0000_ffff]);
+        assert_eq!(last_color(&PALETTE), last_color(heap.as_view()));
+        assert_eq!(
+            PALETTE.as_ptr(),
+            unsafe { PALETTE.as_view().as_slice() }.as_ptr()
+        );
+        assert!(StaticUntypedBytes::from_array([]).is_empty());
+    }
+}
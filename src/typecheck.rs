@@ -0,0 +1,192 @@
+use core::{
+    any::{self, TypeId},
+    ops::Range,
+};
+
+// Remembers the type of the last homogeneous run of writes, as long as that run covers the buffer
+// from its start. Appends and in-place typed writes are both recorded, and writes that are not
+// tracked only ever make the check more lenient. Bytes written as `u8` are raw bytes of no
+// particular type, so they are not tracked either.
+#[derive(Clone, Copy, Debug, Default)]
+pub(crate) struct TypeCheck {
+    written: Option<Written>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Written {
+    id: TypeId,
+    name: &'static str,
+    end: usize,
+}
+
+impl TypeCheck {
+    pub(crate) fn record<T: 'static>(&mut self, range: Range<usize>) {
+        if range.is_empty() {
+            return;
+        }
+        let id = TypeId::of::<T>();
+        self.written = match self.written {
+            _ if id == TypeId::of::<u8>() => None,
+            // Overwriting part of the run with the same type leaves it intact.
+            Some(written) if written.id == id && range.end <= written.end => Some(written),
+            _ if range.start == 0 => Some(Written {
+                id,
+                name: any::type_name::<T>(),
+                end: range.end,
+            }),
+            Some(written) if written.id == id && written.end == range.start => Some(Written {
+                end: range.end,
+                ..written
+            }),
+            _ => None,
+        };
+    }
+
+    pub(crate) fn truncate(&mut self, len: usize) {
+        if len == 0 {
+            self.written = None;
+        } else if let Some(written) = &mut self.written {
+            written.end = written.end.min(len);
+        }
+    }
+
+    // Panics if every one of the `len` bytes was written as a type other than `T`.
+    pub(crate) fn check<T: 'static>(&self, len: usize, method: &str) {
+        if let Some(written) = self.written {
+            assert!(
+                written.end != len || written.id == TypeId::of::<T>(),
+                "`{}` to `{}`, but the bytes were written as `{}`",
+                method,
+                any::type_name::<T>(),
+                written.name
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::UntypedBytes;
+
+    #[test]
+    #[should_panic(expected = "`cast` to `u32`, but the bytes were written as `f32`")]
+    fn cast_to_wrong_type_panics() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push(1.0f32);
+        unsafe { bytes.cast::<u32>() };
+    }
+
+    #[test]
+    #[should_panic(expected = "`try_cast_vec` to `i16`, but the bytes were written as `u16`")]
+    fn try_cast_vec_to_wrong_type_panics() {
+        let bytes = UntypedBytes::from_slice([1u16, 2, 3]);
+        let _ = unsafe { bytes.try_cast_vec::<i16>() };
+    }
+
+    #[test]
+    #[should_panic(expected = "`try_cast` to `u32`, but the bytes were written as `f32`")]
+    fn try_cast_to_wrong_type_panics() {
+        let bytes = UntypedBytes::from_slice([1.0f32]);
+        let _ = unsafe { bytes.try_cast::<u32>() };
+    }
+
+    #[test]
+    fn matching_casts_pass() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push(1.0f32);
+        assert_eq!(unsafe { bytes.cast::<f32>() }, 1.0);
+        bytes.extend_from_slice([2.0f32, 3.0]);
+        assert_eq!(
+            unsafe { bytes.try_cast_vec::<f32>() },
+            Ok([1.0, 2.0, 3.0].to_vec())
+        );
+    }
+
+    // Mixed or untracked writes cannot be checked, so they never panic.
+    #[test]
+    fn mixed_writes_are_lenient() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push(1u16);
+        bytes.push(2i16);
+        assert_eq!(unsafe { bytes.cast::<u32>() }.to_ne_bytes().len(), 4);
+
+        let mut bytes = UntypedBytes::from_slice([7u32]);
+        bytes.push(8u16);
+        assert_eq!(unsafe { bytes.cast::<[u16; 3]>() }.len(), 3);
+    }
+
+    #[test]
+    fn raw_bytes_are_not_checked() {
+        let bytes = UntypedBytes::from_slice(1.5f32.to_ne_bytes());
+        assert_eq!(unsafe { bytes.cast::<f32>() }, 1.5);
+        let mut bytes = UntypedBytes::new();
+        bytes.extend_from_slice(7u64.to_ne_bytes());
+        assert_eq!(unsafe { bytes.cast::<u64>() }, 7);
+    }
+
+    #[test]
+    fn clear_forgets_the_type() {
+        let mut bytes = UntypedBytes::new();
+        bytes.push(1u64);
+        bytes.clear();
+        bytes.push(2.0f64);
+        assert_eq!(unsafe { bytes.cast::<f64>() }, 2.0);
+    }
+
+    #[test]
+    fn record_and_check() {
+        let mut check = TypeCheck::default();
+        check.record::<u16>(0..4);
+        check.record::<u16>(4..8);
+        assert_eq!(check.written.map(|written| written.end), Some(8));
+        check.check::<u16>(8, "cast");
+        // A run of a different type makes the buffer mixed.
+        check.record::<u32>(8..12);
+        assert!(check.written.is_none());
+        check.record::<u32>(0..8);
+        check.truncate(4);
+        assert_eq!(check.written.map(|written| written.end), Some(4));
+        check.check::<u32>(4, "cast");
+        check.record::<u8>(0..1);
+        assert!(check.written.is_none());
+    }
+
+    // In-place typed writes replace the recorded type rather than leaving the old one behind.
+    #[test]
+    fn write_slice_at_records_the_type() {
+        let mut bytes = UntypedBytes::from_slice([1.0f32]);
+        unsafe { bytes.write_slice_at(0, &[7u32]) };
+        assert_eq!(unsafe { bytes.cast::<u32>() }, 7);
+    }
+
+    #[test]
+    fn update_at_records_the_type() {
+        let mut bytes = UntypedBytes::from_slice([1.0f32]);
+        unsafe { bytes.update_at::<u32>(0, |value| *value = 7) };
+        assert_eq!(unsafe { bytes.cast::<u32>() }, 7);
+
+        let mut bytes = UntypedBytes::from_slice([1.0f32]);
+        assert_eq!(
+            unsafe { bytes.try_update_at::<u32>(0, |value| *value = 8) },
+            Some(())
+        );
+        assert_eq!(unsafe { bytes.cast::<u32>() }, 8);
+    }
+
+    #[test]
+    fn element_writes_record_the_type() {
+        let mut bytes = UntypedBytes::from_slice([1.0f32]);
+        unsafe { bytes.fill_as(9u32) };
+        assert_eq!(unsafe { bytes.cast::<u32>() }, 9);
+    }
+
+    // Overwriting part of a run with the same type keeps the whole run checked.
+    #[test]
+    #[should_panic(expected = "`cast` to `u64`, but the bytes were written as `f32`")]
+    fn same_type_overwrite_keeps_the_run() {
+        let mut bytes = UntypedBytes::from_slice([1.0f32, 2.0]);
+        unsafe { bytes.write_slice_at(4, &[3.0f32]) };
+        unsafe { bytes.cast::<u64>() };
+    }
+}
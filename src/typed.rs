@@ -0,0 +1,1285 @@
+// Operations that treat the backing bytes as a packed sequence of `T` elements. The bytes need
+// not be aligned for `T`, so elements are always read and written bytewise.
+
+use crate::{reverse_blocks, view, UntypedBytes};
+use alloc::vec::Vec;
+use core::{
+    cmp::Ordering,
+    marker::PhantomData,
+    mem,
+    ops::{Deref, DerefMut, RangeBounds},
+    ptr, slice,
+};
+
+impl UntypedBytes {
+    // The number of `T` elements in the buffer. Panics if the length is not a multiple of the
+    // element size.
+    pub(crate) fn element_count<T>(&self) -> usize {
+        let size = mem::size_of::<T>();
+        assert_ne!(size, 0, "elements must not be zero-sized");
+        assert_eq!(
+            self.len() % size,
+            0,
+            "length {} is not a multiple of the element size {}",
+            self.len(),
+            size
+        );
+        self.len() / size
+    }
+
+    #[inline]
+    pub(crate) unsafe fn read_element<T: Copy>(&self, index: usize) -> T {
+        let offset = index * mem::size_of::<T>();
+        debug_assert!(offset + mem::size_of::<T>() <= self.len());
+        ptr::read_unaligned(self.bytes.as_ptr().add(offset) as *const T)
+    }
+
+    #[inline]
+    pub(crate) unsafe fn write_element<T: Copy + 'static>(&mut self, index: usize, value: T) {
+        let offset = index * mem::size_of::<T>();
+        debug_assert!(offset + mem::size_of::<T>() <= self.len());
+        ptr::write_unaligned(self.bytes.as_mut_ptr().add(offset) as *mut T, value);
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck
+            .record::<T>(offset..offset + mem::size_of::<T>());
+    }
+
+    #[inline]
+    pub(crate) unsafe fn swap_elements<T>(&mut self, a: usize, b: usize) {
+        if a != b {
+            let size = mem::size_of::<T>();
+            let ptr = self.bytes.as_mut_ptr();
+            ptr::swap_nonoverlapping(ptr.add(a * size), ptr.add(b * size), size)
+        }
+    }
+
+    // Reinterprets the buffer as `&mut [T]` if the backing bytes happen to be aligned for `T`.
+    pub(crate) unsafe fn as_typed_mut<T>(&mut self) -> Option<&mut [T]> {
+        let count = self.element_count::<T>();
+        let ptr = self.bytes.as_mut_ptr() as *mut T;
+        if ptr.is_aligned() {
+            Some(slice::from_raw_parts_mut(ptr, count))
+        } else {
+            None
+        }
+    }
+
+    /// Returns a guard that derefs to the buffer as a `[T]`. This is only safe if the backing
+    /// bytes were created from values of type `T`.
+    pub unsafe fn view_mut<T: Copy + Send + Sync + 'static>(&mut self) -> TypedViewMut<'_, T> {
+        // Writes through the view are not seen individually, so the whole buffer counts as `T`.
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.record::<T>(0..self.len());
+        if self.as_typed_mut::<T>().is_none() {
+            return self.view_mut_copied();
+        }
+        TypedViewMut {
+            bytes: self,
+            copy: None,
+        }
+    }
+
+    // The `view_mut` fallback for backing bytes that are not aligned for `T`, which works on a
+    // copy of the elements.
+    pub(crate) unsafe fn view_mut_copied<T: Copy + Send + Sync + 'static>(
+        &mut self,
+    ) -> TypedViewMut<'_, T> {
+        let copy = (0..self.element_count::<T>())
+            .map(|i| self.read_element(i))
+            .collect();
+        TypedViewMut {
+            bytes: self,
+            copy: Some(copy),
+        }
+    }
+
+    /// Stably sorts the buffer as a sequence of `T` elements. This is only safe if the backing
+    /// bytes were created from values of type `T`.
+    ///
+    /// When the backing bytes are aligned for `T` this sorts in place, otherwise the elements are
+    /// copied out, sorted, and copied back.
+    pub unsafe fn sort_as<T: Copy + Send + Sync + Ord + 'static>(&mut self) {
+        self.sort_by_as(T::cmp)
+    }
+
+    /// Like `sort_as`, but unstable. When the backing bytes are not aligned for `T`, this falls
+    /// back to a heapsort that swaps elements bytewise and does not allocate.
+    pub unsafe fn sort_unstable_as<T: Copy + Send + Sync + Ord + 'static>(&mut self) {
+        self.sort_unstable_by_as(T::cmp)
+    }
+
+    /// Stably sorts the buffer as a sequence of `T` elements with the comparator `compare`. See
+    /// `sort_as`.
+    pub unsafe fn sort_by_as<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        compare: impl FnMut(&T, &T) -> Ordering,
+    ) {
+        if let Some(elements) = self.as_typed_mut::<T>() {
+            return elements.sort_by(compare);
+        }
+        self.sort_by_copied(compare)
+    }
+
+    // The `sort_by_as` fallback for backing bytes that are not aligned for `T`.
+    pub(crate) unsafe fn sort_by_copied<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        compare: impl FnMut(&T, &T) -> Ordering,
+    ) {
+        let mut elements: Vec<T> = (0..self.element_count::<T>())
+            .map(|i| self.read_element(i))
+            .collect();
+        elements.sort_by(compare);
+        for (i, elem) in elements.into_iter().enumerate() {
+            self.write_element(i, elem)
+        }
+    }
+
+    /// Unstably sorts the buffer as a sequence of `T` elements with the comparator `compare`. See
+    /// `sort_unstable_as`.
+    pub unsafe fn sort_unstable_by_as<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        compare: impl FnMut(&T, &T) -> Ordering,
+    ) {
+        if let Some(elements) = self.as_typed_mut::<T>() {
+            return elements.sort_unstable_by(compare);
+        }
+        self.heapsort_by(compare)
+    }
+
+    // The `sort_unstable_by_as` fallback for backing bytes that are not aligned for `T`.
+    pub(crate) unsafe fn heapsort_by<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        mut compare: impl FnMut(&T, &T) -> Ordering,
+    ) {
+        let count = self.element_count::<T>();
+        let mut is_less = |this: &mut Self, a: usize, b: usize| {
+            compare(&this.read_element::<T>(a), &this.read_element::<T>(b)) == Ordering::Less
+        };
+        let mut sift_down = |this: &mut Self, mut node: usize, end: usize| loop {
+            let mut child = 2 * node + 1;
+            if child >= end {
+                break;
+            }
+            if child + 1 < end && is_less(this, child, child + 1) {
+                child += 1;
+            }
+            if !is_less(this, node, child) {
+                break;
+            }
+            this.swap_elements::<T>(node, child);
+            node = child;
+        };
+        for node in (0..count / 2).rev() {
+            sift_down(self, node, count);
+        }
+        for end in (1..count).rev() {
+            self.swap_elements::<T>(0, end);
+            sift_down(self, 0, end);
+        }
+    }
+
+    /// Stably sorts the buffer as a sequence of `T` elements by the key extracted by `f`. See
+    /// `sort_as`.
+    pub unsafe fn sort_by_key_as<T: Copy + Send + Sync + 'static, K: Ord>(
+        &mut self,
+        mut f: impl FnMut(&T) -> K,
+    ) {
+        self.sort_by_as(|a: &T, b: &T| f(a).cmp(&f(b)))
+    }
+
+    /// Removes consecutive duplicate `T` elements, comparing them bytewise. This is only safe if
+    /// the backing bytes were created from values of type `T`.
+    ///
+    /// Padding bytes take part in the comparison, so equal values of a type with padding may not
+    /// be deduplicated. Use `dedup_by_as` for such types.
+    pub unsafe fn dedup_as<T: Copy + Send + Sync + 'static>(&mut self) {
+        let size = mem::size_of::<T>();
+        self.dedup_blocks::<T>(|bytes, current, previous| {
+            bytes[current * size..][..size] == bytes[previous * size..][..size]
+        })
+    }
+
+    /// Removes consecutive `T` elements for which `same(current, previous)` returns `true`, with
+    /// the semantics of `Vec::dedup_by`. This is only safe if the backing bytes were created from
+    /// values of type `T`.
+    pub unsafe fn dedup_by_as<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        mut same: impl FnMut(&T, &T) -> bool,
+    ) {
+        let size = mem::size_of::<T>();
+        self.dedup_blocks::<T>(|bytes, current, previous| {
+            let read =
+                |index: usize| ptr::read_unaligned(bytes.as_ptr().add(index * size) as *const T);
+            same(&read(current), &read(previous))
+        })
+    }
+
+    // Compacts the elements for which `same(bytes, current, previous)` is false toward the front,
+    // where `previous` is the last element kept.
+    unsafe fn dedup_blocks<T>(&mut self, mut same: impl FnMut(&[u8], usize, usize) -> bool) {
+        let count = self.element_count::<T>();
+        if count == 0 {
+            return;
+        }
+        let size = mem::size_of::<T>();
+        let mut kept = 1;
+        for current in 1..count {
+            if !same(&self.bytes, current, kept - 1) {
+                if current != kept {
+                    self.bytes
+                        .copy_within(current * size..(current + 1) * size, kept * size);
+                }
+                kept += 1;
+            }
+        }
+        self.truncate_bytes(kept * size)
+    }
+
+    /// Keeps only the `T` elements for which `pred` returns `true`, compacting them toward the
+    /// front in order and truncating the buffer to the kept elements. `pred` is given a bytewise
+    /// copy of each element, so alignment does not matter. This is only safe if the backing bytes
+    /// were created from values of type `T`.
+    pub unsafe fn retain_as<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        mut pred: impl FnMut(&T) -> bool,
+    ) {
+        let count = self.element_count::<T>();
+        let size = mem::size_of::<T>();
+        let mut kept = 0;
+        for current in 0..count {
+            if pred(&self.read_element(current)) {
+                if current != kept {
+                    self.bytes
+                        .copy_within(current * size..(current + 1) * size, kept * size);
+                }
+                kept += 1;
+            }
+        }
+        self.truncate_bytes(kept * size)
+    }
+
+    /// Removes and returns the `T` element at `element_index`, replacing it with the last element.
+    /// This does not preserve ordering, but is O(1). This is only safe if the backing bytes were
+    /// created from values of type `T`. Panics if the index is out of bounds.
+    pub unsafe fn swap_remove_as<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        element_index: usize,
+    ) -> T {
+        let count = self.element_count::<T>();
+        assert!(
+            element_index < count,
+            "`swap_remove_as` index {} is out of bounds for {} elements",
+            element_index,
+            count
+        );
+        let size = mem::size_of::<T>();
+        let removed = self.read_element(element_index);
+        let last = count - 1;
+        if element_index != last {
+            self.bytes
+                .copy_within(last * size..count * size, element_index * size);
+        }
+        self.truncate_bytes(last * size);
+        removed
+    }
+
+    /// Reverses the order of the `T` elements in place. The bytes are only moved, never read as
+    /// `T`, but this is only meaningful if the backing bytes were created from values of type `T`.
+    pub unsafe fn reverse_as<T: Copy + Send + Sync + 'static>(&mut self) {
+        self.element_count::<T>();
+        self.reverse_chunks(mem::size_of::<T>())
+    }
+
+    /// Rotates the `T` elements in place so that the element at `n_elements` (modulo the element
+    /// count) becomes the first, without allocating. This is only meaningful if the backing bytes
+    /// were created from values of type `T`.
+    pub unsafe fn rotate_left_as<T: Copy + Send + Sync + 'static>(&mut self, n_elements: usize) {
+        let count = self.element_count::<T>();
+        if count != 0 {
+            self.rotate_blocks(mem::size_of::<T>(), n_elements % count)
+        }
+    }
+
+    /// Rotates the `T` elements in place so that the last `n_elements` (modulo the element count)
+    /// move to the front. See `rotate_left_as`.
+    pub unsafe fn rotate_right_as<T: Copy + Send + Sync + 'static>(&mut self, n_elements: usize) {
+        let count = self.element_count::<T>();
+        if count != 0 {
+            self.rotate_blocks(mem::size_of::<T>(), (count - n_elements % count) % count)
+        }
+    }
+
+    // Rotates left by `mid` blocks of `size` bytes using three reversals.
+    fn rotate_blocks(&mut self, size: usize, mid: usize) {
+        if mid == 0 {
+            return;
+        }
+        let (front, back) = self.bytes.split_at_mut(mid * size);
+        reverse_blocks(front, size);
+        reverse_blocks(back, size);
+        reverse_blocks(&mut self.bytes, size);
+    }
+
+    /// Replaces every `T` element with the result of passing it to `f`. This is only safe if the
+    /// backing bytes were created from values of type `T`. Panics if the length is not a
+    /// multiple of the element size.
+    pub unsafe fn map_in_place<T: Copy + Send + Sync + 'static>(
+        &mut self,
+        mut f: impl FnMut(T) -> T,
+    ) {
+        for i in 0..self.element_count::<T>() {
+            let value = f(self.read_element(i));
+            self.write_element(i, value)
+        }
+    }
+
+    /// Returns a new buffer holding `f` applied to every `Src` element, allocating the output once.
+    /// This is only safe if the backing bytes were created from values of type `Src`. Panics if
+    /// the length is not a multiple of the element size.
+    pub unsafe fn convert<Src, Dst>(&self, mut f: impl FnMut(Src) -> Dst) -> UntypedBytes
+    where
+        Src: Copy + Send + Sync + 'static,
+        Dst: Copy + Send + Sync + 'static,
+    {
+        let count = self.element_count::<Src>();
+        let mut result = UntypedBytes::with_capacity(count * mem::size_of::<Dst>());
+        result.extend_with(count, |i| f(self.read_element(i)));
+        result
+    }
+
+    /// Like `convert`, but consumes the buffer and converts in place when `Dst` is no larger than
+    /// `Src`, reusing the allocation.
+    pub unsafe fn convert_in_place<Src, Dst>(
+        mut self,
+        mut f: impl FnMut(Src) -> Dst,
+    ) -> UntypedBytes
+    where
+        Src: Copy + Send + Sync + 'static,
+        Dst: Copy + Send + Sync + 'static,
+    {
+        if mem::size_of::<Dst>() > mem::size_of::<Src>() {
+            return self.convert(f);
+        }
+        let count = self.element_count::<Src>();
+        // Element `i` is written at or before where it was read, so it never overwrites an
+        // element that has not been read yet.
+        for i in 0..count {
+            let value = f(self.read_element::<Src>(i));
+            self.write_element(i, value)
+        }
+        self.truncate_bytes(count * mem::size_of::<Dst>());
+        #[cfg(all(feature = "debug-typecheck", debug_assertions))]
+        self.typecheck.record::<Dst>(0..self.len());
+        self
+    }
+
+    /// Like `convert`, but stops at and returns the first error from `f`, as when narrowing indices
+    /// that may not fit.
+    pub unsafe fn try_convert<Src, Dst, E>(
+        &self,
+        mut f: impl FnMut(Src) -> Result<Dst, E>,
+    ) -> Result<UntypedBytes, E>
+    where
+        Src: Copy + Send + Sync + 'static,
+        Dst: Copy + Send + Sync + 'static,
+    {
+        let count = self.element_count::<Src>();
+        let mut result = UntypedBytes::with_capacity(count * mem::size_of::<Dst>());
+        for i in 0..count {
+            result.push(f(self.read_element(i))?)
+        }
+        Ok(result)
+    }
+
+    /// Overwrites every `T` element with `value`, keeping the length unchanged. Panics if the
+    /// length is not a multiple of the element size.
+    pub unsafe fn fill_as<T: Copy + Send + Sync + 'static>(&mut self, value: T) {
+        if self.element_count::<T>() == 0 {
+            return;
+        }
+        self.write_element(0, value);
+        // Double the filled prefix until it covers the buffer.
+        let mut filled = mem::size_of::<T>();
+        while filled < self.len() {
+            let n = filled.min(self.len() - filled);
+            self.bytes.copy_within(..n, filled);
+            filled += n;
+        }
+    }
+
+    /// Copies the `T` elements in `range`, given in element units, into a new `Vec<T>`. This is
+    /// only safe if the backing bytes were created from values of type `T`. Panics if the range is
+    /// out of bounds.
+    pub unsafe fn to_vec_of<T: Copy + Send + Sync + 'static, R: RangeBounds<usize>>(
+        &self,
+        range: R,
+    ) -> Vec<T> {
+        let range = view::resolve(range, self.element_count::<T>());
+        let size = mem::size_of::<T>();
+        let mut result = Vec::<T>::with_capacity(range.len());
+        ptr::copy_nonoverlapping(
+            self.bytes.as_ptr().add(range.start * size),
+            result.as_mut_ptr() as *mut u8,
+            range.len() * size,
+        );
+        result.set_len(range.len());
+        result
+    }
+
+    /// Consumes the buffer, returning an iterator that yields each `T` element by bytewise copy.
+    /// This is only safe if the backing bytes were created from values of type `T`.
+    pub unsafe fn into_iter_as<T: Copy + Send + Sync + 'static>(self) -> IntoIterAs<T> {
+        let size = mem::size_of::<T>();
+        assert_ne!(size, 0, "elements must not be zero-sized");
+        debug_assert_eq!(
+            self.len() % size,
+            0,
+            "length {} is not a multiple of the element size {}",
+            self.len(),
+            size
+        );
+        IntoIterAs {
+            back: self.len() / size,
+            front: 0,
+            bytes: self.bytes,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Binary searches a sorted buffer of `T` elements for `value` with the semantics of
+    /// `slice::binary_search`, returning an element index. This is only safe if the backing bytes
+    /// were created from values of type `T`.
+    pub unsafe fn binary_search_as<T: Copy + Send + Sync + Ord + 'static>(
+        &self,
+        value: &T,
+    ) -> Result<usize, usize> {
+        self.binary_search_by_as(|elem: &T| elem.cmp(value))
+    }
+
+    /// Binary searches a sorted buffer of `T` elements with the semantics of
+    /// `slice::binary_search_by`, returning an element index. This is only safe if the backing
+    /// bytes were created from values of type `T`.
+    pub unsafe fn binary_search_by_as<T: Copy + Send + Sync + 'static>(
+        &self,
+        mut f: impl FnMut(&T) -> Ordering,
+    ) -> Result<usize, usize> {
+        let mut left = 0;
+        let mut right = self.element_count::<T>();
+        while left < right {
+            let mid = left + (right - left) / 2;
+            match f(&self.read_element(mid)) {
+                Ordering::Less => left = mid + 1,
+                Ordering::Greater => right = mid,
+                Ordering::Equal => return Ok(mid),
+            }
+        }
+        Err(left)
+    }
+
+    /// Returns the index of the first `T` element for which `pred` is false, with the semantics
+    /// of `slice::partition_point`. This is only safe if the backing bytes were created from
+    /// values of type `T`.
+    pub unsafe fn partition_point_as<T: Copy + Send + Sync + 'static>(
+        &self,
+        mut pred: impl FnMut(&T) -> bool,
+    ) -> usize {
+        let mut left = 0;
+        let mut right = self.element_count::<T>();
+        while left < right {
+            let mid = left + (right - left) / 2;
+            if pred(&self.read_element(mid)) {
+                left = mid + 1;
+            } else {
+                right = mid;
+            }
+        }
+        left
+    }
+
+    /// Splits a buffer of `channels` interleaved `T` streams back into one `Vec` per channel.
+    /// This is only safe if the backing bytes were created from values of type `T`.
+    pub unsafe fn deinterleave<T: Copy + Send + Sync + 'static>(
+        &self,
+        channels: usize,
+    ) -> Vec<Vec<T>> {
+        let count = self.element_count::<T>();
+        assert_ne!(channels, 0, "`deinterleave` requires at least one channel");
+        assert_eq!(
+            count % channels,
+            0,
+            "element count {} is not a multiple of the channel count {}",
+            count,
+            channels
+        );
+        let mut result: Vec<Vec<T>> = (0..channels)
+            .map(|_| Vec::with_capacity(count / channels))
+            .collect();
+        for i in 0..count {
+            result[i % channels].push(self.read_element(i))
+        }
+        result
+    }
+}
+
+/// A consuming iterator over the `T` elements of an `UntypedBytes`, created by
+/// `UntypedBytes::into_iter_as`. The buffer is freed when the iterator is dropped.
+pub struct IntoIterAs<T> {
+    bytes: Vec<u8>,
+    front: usize,
+    back: usize,
+    _marker: PhantomData<T>,
+}
+
+impl<T: Copy> IntoIterAs<T> {
+    #[inline]
+    fn read(&self, index: usize) -> T {
+        unsafe {
+            ptr::read_unaligned(self.bytes.as_ptr().add(index * mem::size_of::<T>()) as *const T)
+        }
+    }
+}
+
+impl<T: Copy> Iterator for IntoIterAs<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.front += 1;
+        Some(self.read(self.front - 1))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let len = self.back - self.front;
+        (len, Some(len))
+    }
+}
+
+impl<T: Copy> DoubleEndedIterator for IntoIterAs<T> {
+    fn next_back(&mut self) -> Option<T> {
+        if self.front == self.back {
+            return None;
+        }
+        self.back -= 1;
+        Some(self.read(self.back))
+    }
+}
+
+impl<T: Copy> ExactSizeIterator for IntoIterAs<T> {}
+
+/// A mutable typed view of an `UntypedBytes`, created by `UntypedBytes::view_mut`.
+///
+/// The view borrows the buffer mutably, so the bytes cannot be changed through any other path
+/// while it is alive. If the backing bytes are not aligned for `T`, the view works on a copy that
+/// is written back when the view is dropped.
+pub struct TypedViewMut<'a, T: Copy + 'static> {
+    bytes: &'a mut UntypedBytes,
+    copy: Option<Vec<T>>,
+}
+
+impl<T: Copy + 'static> Deref for TypedViewMut<'_, T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match &self.copy {
+            Some(copy) => copy,
+            None => unsafe {
+                slice::from_raw_parts(
+                    self.bytes.bytes.as_ptr() as *const T,
+                    self.bytes.len() / mem::size_of::<T>(),
+                )
+            },
+        }
+    }
+}
+
+impl<T: Copy + 'static> DerefMut for TypedViewMut<'_, T> {
+    fn deref_mut(&mut self) -> &mut [T] {
+        match &mut self.copy {
+            Some(copy) => copy,
+            None => unsafe {
+                slice::from_raw_parts_mut(
+                    self.bytes.bytes.as_mut_ptr() as *mut T,
+                    self.bytes.len() / mem::size_of::<T>(),
+                )
+            },
+        }
+    }
+}
+
+impl<T: Copy + 'static> Drop for TypedViewMut<'_, T> {
+    fn drop(&mut self) {
+        if let Some(copy) = self.copy.take() {
+            for (i, elem) in copy.into_iter().enumerate() {
+                unsafe { self.bytes.write_element(i, elem) }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::vec;
+    use core::convert::TryFrom;
+
+    // There is no `interleave` builder, so the channels are zipped by hand.
+    fn interleave<T: Copy + Send + Sync + 'static>(channels: &[&[T]]) -> UntypedBytes {
+        let mut bytes = UntypedBytes::new();
+        for i in 0..channels[0].len() {
+            for channel in channels {
+                bytes.push(channel[i]);
+            }
+        }
+        bytes
+    }
+
+    #[test]
+    fn deinterleave_reproduces_channels() {
+        let left = [0.0f32, 0.5, -0.5, 1.0];
+        let right = [1.0f32, -1.0, 0.25, 0.0];
+        let bytes = interleave(&[&left, &right]);
+        assert_eq!(
+            unsafe { bytes.deinterleave::<f32>(2) },
+            vec![left.to_vec(), right.to_vec()]
+        );
+    }
+
+    #[test]
+    fn deinterleave_three_and_one_channels() {
+        let bytes = UntypedBytes::from_slice([1u32, 2, 3, 4, 5, 6]);
+        assert_eq!(
+            unsafe { bytes.deinterleave::<u32>(3) },
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]]
+        );
+        assert_eq!(
+            unsafe { bytes.deinterleave::<u32>(1) },
+            vec![vec![1, 2, 3, 4, 5, 6]]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a multiple of the channel count")]
+    fn deinterleave_rejects_uneven_channels() {
+        unsafe { UntypedBytes::from_slice([1u16, 2, 3]).deinterleave::<u16>(2) };
+    }
+
+    // Deterministic pseudo-random values for comparing against `Vec` operations.
+    fn xorshift(state: &mut u32) -> u32 {
+        *state ^= *state << 13;
+        *state ^= *state >> 17;
+        *state ^= *state << 5;
+        *state
+    }
+
+    fn random_vec(len: usize, modulo: u32) -> Vec<u32> {
+        let mut state = 0x2545_f491;
+        (0..len).map(|_| xorshift(&mut state) % modulo).collect()
+    }
+
+    // Over-aligned and padding free, so its elements must be read and written without assuming
+    // the buffer is aligned for it.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+    #[repr(C, align(64))]
+    struct Wide([u32; 16]);
+
+    fn wide(key: u32, tag: u32) -> Wide {
+        let mut fields = [0; 16];
+        fields[0] = key;
+        fields[15] = tag;
+        Wide(fields)
+    }
+
+    #[test]
+    fn sort_as_matches_vec_sort() {
+        let values = random_vec(200, 50);
+        let mut expected = values.clone();
+        expected.sort();
+
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.sort_as::<u32>() };
+        assert_eq!(unsafe { bytes.to_vec_of::<u32, _>(..) }, expected);
+
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.sort_unstable_as::<u32>() };
+        assert_eq!(unsafe { bytes.to_vec_of::<u32, _>(..) }, expected);
+
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.sort_by_as::<u32>(|a, b| b.cmp(a)) };
+        expected.reverse();
+        assert_eq!(unsafe { bytes.to_vec_of::<u32, _>(..) }, expected);
+    }
+
+    #[test]
+    fn sort_fallbacks_match_vec_sort() {
+        let values: Vec<Wide> = random_vec(64, 8)
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| wide(key, i as u32))
+            .collect();
+
+        // Whether the public methods take the fallback depends on where the allocator put the
+        // buffer, so the fallbacks are also called directly.
+        let mut expected = values.clone();
+        expected.sort_by_key(|w| w.0[0]);
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.sort_by_key_as::<Wide, _>(|w| w.0[0]) };
+        assert_eq!(unsafe { bytes.to_vec_of::<Wide, _>(..) }, expected);
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.sort_by_copied::<Wide>(|a, b| a.0[0].cmp(&b.0[0])) };
+        assert_eq!(unsafe { bytes.to_vec_of::<Wide, _>(..) }, expected);
+
+        let mut expected = values.clone();
+        expected.sort_unstable();
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.sort_unstable_as::<Wide>() };
+        assert_eq!(unsafe { bytes.to_vec_of::<Wide, _>(..) }, expected);
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.heapsort_by::<Wide>(Wide::cmp) };
+        assert_eq!(unsafe { bytes.to_vec_of::<Wide, _>(..) }, expected);
+    }
+
+    #[test]
+    fn sort_by_key_as_is_stable() {
+        let records: Vec<(u16, u16)> = random_vec(100, 4)
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| (key as u16, i as u16))
+            .collect();
+        let mut expected = records.clone();
+        expected.sort_by_key(|r| r.0);
+        let mut bytes = UntypedBytes::from_slice(&records[..]);
+        unsafe { bytes.sort_by_key_as::<(u16, u16), _>(|r| r.0) };
+        assert_eq!(unsafe { bytes.to_vec_of::<(u16, u16), _>(..) }, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a multiple of the element size")]
+    fn sort_as_rejects_partial_elements() {
+        unsafe { UntypedBytes::from_slice([0u8; 6]).sort_as::<u32>() };
+    }
+
+    const SORTED_KEYS: [u32; 6] = [10, 20, 30, 40, 50, 60];
+
+    fn lookup_table() -> UntypedBytes {
+        let records: Vec<(u32, f32)> = SORTED_KEYS.iter().map(|&k| (k, k as f32 / 10.0)).collect();
+        UntypedBytes::from_slice(records)
+    }
+
+    #[test]
+    fn binary_search_by_as_cases() {
+        let bytes = lookup_table();
+        let search =
+            |key: u32| unsafe { bytes.binary_search_by_as(|r: &(u32, f32)| r.0.cmp(&key)) };
+        assert_eq!(search(40), Ok(3));
+        assert_eq!(search(10), Ok(0));
+        assert_eq!(search(60), Ok(5));
+        assert_eq!(search(35), Err(3));
+        assert_eq!(search(5), Err(0));
+        assert_eq!(search(70), Err(6));
+        for key in 0..70 {
+            assert_eq!(search(key), SORTED_KEYS.binary_search(&key));
+        }
+        let empty = UntypedBytes::new();
+        assert_eq!(
+            unsafe { empty.binary_search_by_as(|r: &(u32, f32)| r.0.cmp(&1)) },
+            Err(0)
+        );
+    }
+
+    #[test]
+    fn partition_point_as_range_query() {
+        let bytes = lookup_table();
+        let start = unsafe { bytes.partition_point_as(|r: &(u32, f32)| r.0 < 25) };
+        let end = unsafe { bytes.partition_point_as(|r: &(u32, f32)| r.0 <= 50) };
+        assert_eq!(start..end, 2..5);
+        assert_eq!(
+            unsafe { bytes.partition_point_as(|_: &(u32, f32)| true) },
+            6
+        );
+        assert_eq!(
+            unsafe { bytes.partition_point_as(|_: &(u32, f32)| false) },
+            0
+        );
+    }
+
+    fn deduped(values: &[u32]) -> Vec<u32> {
+        let mut bytes = UntypedBytes::from_slice(values);
+        unsafe { bytes.dedup_as::<u32>() };
+        unsafe { bytes.to_vec_of(..) }
+    }
+
+    #[test]
+    fn dedup_as_cases() {
+        assert_eq!(deduped(&[1, 2, 3, 4]), [1, 2, 3, 4]);
+        assert_eq!(deduped(&[7; 5]), [7]);
+        assert_eq!(deduped(&[1, 2, 1, 2, 1]), [1, 2, 1, 2, 1]);
+        assert_eq!(deduped(&[1, 1, 2, 2, 2, 1, 3, 3]), [1, 2, 1, 3]);
+        assert_eq!(deduped(&[]), [] as [u32; 0]);
+        for values in [random_vec(100, 3), random_vec(30, 50)].iter() {
+            let mut expected = values.clone();
+            expected.dedup();
+            assert_eq!(deduped(values), expected);
+        }
+    }
+
+    #[test]
+    fn dedup_by_as_decodes_values() {
+        let mut bytes = UntypedBytes::from_slice([0.0f32, -0.0, 1.0, 1.0, f32::NAN, f32::NAN]);
+        unsafe { bytes.dedup_by_as::<f32>(|a, b| a == b) };
+        let kept: Vec<f32> = unsafe { bytes.to_vec_of(..) };
+        assert_eq!(kept.len(), 4);
+        assert_eq!(&kept[..2], &[0.0, 1.0]);
+        assert!(kept[2].is_nan() && kept[3].is_nan());
+
+        // Bytewise, `0.0` and `-0.0` differ but the two NaNs are identical.
+        let mut bytes = UntypedBytes::from_slice([0.0f32, -0.0, f32::NAN, f32::NAN]);
+        unsafe { bytes.dedup_as::<f32>() };
+        assert_eq!(bytes.len(), 12);
+    }
+
+    #[test]
+    fn view_mut_changes_persist() {
+        let mut bytes = UntypedBytes::from_slice([1u32, 2, 3]);
+        {
+            let mut view = unsafe { bytes.view_mut::<u32>() };
+            assert_eq!(&view[..], &[1, 2, 3]);
+            view[1] = 20;
+            view.iter_mut().for_each(|v| *v += 1);
+        }
+        assert_eq!(unsafe { bytes.to_vec_of::<u32, _>(..) }, [2, 21, 4]);
+    }
+
+    #[test]
+    fn view_mut_copy_writes_back_on_drop() {
+        let mut bytes = UntypedBytes::from_slice([wide(1, 0), wide(2, 0)]);
+        {
+            let mut view = unsafe { bytes.view_mut_copied::<Wide>() };
+            view.swap(0, 1);
+            view[0].0[15] = 9;
+        }
+        assert_eq!(
+            unsafe { bytes.to_vec_of::<Wide, _>(..) },
+            [wide(2, 9), wide(1, 0)]
+        );
+    }
+
+    fn retained(values: &[u32], mut pred: impl FnMut(&u32) -> bool) -> Vec<u32> {
+        let mut bytes = UntypedBytes::from_slice(values);
+        unsafe { bytes.retain_as::<u32>(|v| pred(v)) };
+        assert_eq!(bytes.len() % 4, 0);
+        unsafe { bytes.to_vec_of(..) }
+    }
+
+    #[test]
+    fn retain_as_kills_every_other_element() {
+        let particles: Vec<u32> = (0..11).collect();
+        let survivors = retained(&particles, |p| p % 2 == 0);
+        assert_eq!(survivors, [0, 2, 4, 6, 8, 10]);
+    }
+
+    #[test]
+    fn retain_as_keep_none_and_keep_all() {
+        let particles: Vec<u32> = (0..8).collect();
+        assert_eq!(retained(&particles, |_| false), [] as [u32; 0]);
+        assert_eq!(retained(&particles, |_| true), particles);
+        assert_eq!(retained(&[], |_| unreachable!()), [] as [u32; 0]);
+    }
+
+    #[test]
+    fn swap_remove_as_matches_vec() {
+        let mut model: Vec<(u32, u16, u16)> = (0..64).map(|i| (i, i as u16 * 3, 7)).collect();
+        let mut bytes = UntypedBytes::from_slice(&model[..]);
+        let mut state = 0x9e37_79b9;
+        while !model.is_empty() {
+            let index = xorshift(&mut state) as usize % model.len();
+            let removed = unsafe { bytes.swap_remove_as::<(u32, u16, u16)>(index) };
+            assert_eq!(removed, model.swap_remove(index));
+            assert_eq!(unsafe { bytes.to_vec_of::<(u32, u16, u16), _>(..) }, model);
+        }
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "`swap_remove_as` index 3 is out of bounds for 3 elements")]
+    fn swap_remove_as_out_of_bounds() {
+        unsafe { UntypedBytes::from_slice([1u64, 2, 3]).swap_remove_as::<u64>(3) };
+    }
+
+    fn reversed(values: &[[u8; 3]]) -> Vec<[u8; 3]> {
+        let mut bytes = UntypedBytes::from_slice(values);
+        unsafe { bytes.reverse_as::<[u8; 3]>() };
+        unsafe { bytes.to_vec_of(..) }
+    }
+
+    #[test]
+    fn reverse_as_counts() {
+        let strip = [[1, 1, 1], [2, 2, 2], [3, 3, 3], [4, 4, 4]];
+        let mut expected = strip.to_vec();
+        expected.reverse();
+        assert_eq!(reversed(&strip), expected);
+        assert_eq!(reversed(&strip[..3]), [[3, 3, 3], [2, 2, 2], [1, 1, 1]]);
+        assert_eq!(reversed(&strip[..1]), [[1, 1, 1]]);
+        assert_eq!(reversed(&[]), [] as [[u8; 3]; 0]);
+    }
+
+    #[test]
+    fn reverse_chunks_keeps_bytes_within_chunks() {
+        let mut bytes = UntypedBytes::from_slice([1u8, 2, 3, 4, 5, 6, 7, 8, 9, 10]);
+        bytes.reverse_chunks(5);
+        assert_eq!(&bytes.bytes[..], &[6, 7, 8, 9, 10, 1, 2, 3, 4, 5]);
+        bytes.reverse_chunks(10);
+        assert_eq!(&bytes.bytes[..], &[6, 7, 8, 9, 10, 1, 2, 3, 4, 5]);
+        bytes.reverse_chunks(1);
+        assert_eq!(&bytes.bytes[..], &[5, 4, 3, 2, 1, 10, 9, 8, 7, 6]);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a multiple of the chunk size")]
+    fn reverse_chunks_rejects_uneven_chunks() {
+        UntypedBytes::from_slice([0u8; 10]).reverse_chunks(4);
+    }
+
+    #[test]
+    fn rotate_as_matches_vec_rotate() {
+        for &len in &[1usize, 2, 5, 16, 33] {
+            let values: Vec<[u8; 3]> = random_vec(len, 256)
+                .into_iter()
+                .map(|v| [v as u8, (v >> 8) as u8, len as u8])
+                .collect();
+            for &n in &[0, 1, len / 2, len - 1, len, 2 * len, 3 * len + 1] {
+                let mut expected = values.clone();
+                expected.rotate_left(n % len);
+                let mut bytes = UntypedBytes::from_slice(&values[..]);
+                unsafe { bytes.rotate_left_as::<[u8; 3]>(n) };
+                assert_eq!(unsafe { bytes.to_vec_of::<[u8; 3], _>(..) }, expected);
+
+                let mut expected = values.clone();
+                expected.rotate_right(n % len);
+                let mut bytes = UntypedBytes::from_slice(&values[..]);
+                unsafe { bytes.rotate_right_as::<[u8; 3]>(n) };
+                assert_eq!(unsafe { bytes.to_vec_of::<[u8; 3], _>(..) }, expected);
+            }
+        }
+    }
+
+    #[test]
+    fn rotate_as_empty() {
+        let mut bytes = UntypedBytes::new();
+        unsafe { bytes.rotate_left_as::<u64>(3) };
+        unsafe { bytes.rotate_right_as::<u64>(3) };
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a multiple of the element size")]
+    fn rotate_as_rejects_partial_elements() {
+        let mut bytes = UntypedBytes::from_slice([1u8, 2, 3]);
+        unsafe { bytes.rotate_left_as::<u16>(1) };
+    }
+
+    #[test]
+    fn fill_as_overwrites_every_element() {
+        let sentinel = wide(u32::MAX, 7);
+        for &len in &[1usize, 2, 3, 7, 8, 33] {
+            let values: Vec<Wide> = (0..len as u32).map(|i| wide(i, i)).collect();
+            let mut bytes = UntypedBytes::from_slice(&values[..]);
+            unsafe { bytes.fill_as(sentinel) };
+            assert_eq!(bytes.len(), len * mem::size_of::<Wide>());
+            for i in 0..len {
+                let value: Wide = unsafe { bytes.as_view().read_at(i * mem::size_of::<Wide>()) };
+                assert_eq!(value, sentinel);
+            }
+        }
+    }
+
+    #[test]
+    fn fill_as_empty() {
+        let mut bytes = UntypedBytes::new();
+        unsafe { bytes.fill_as(1u32) };
+        assert!(bytes.is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a multiple of the element size")]
+    fn fill_as_rejects_partial_elements() {
+        let mut bytes = UntypedBytes::from_slice([1u8, 2, 3]);
+        unsafe { bytes.fill_as(0u16) };
+    }
+
+    #[test]
+    fn fill_bytes_patterns() {
+        let mut bytes = UntypedBytes::from_slice([1u32, 2, 3]);
+        bytes.fill_bytes(0xff);
+        for i in 0..3 {
+            assert_eq!(unsafe { bytes.as_view().read_at::<u32>(i * 4) }, u32::MAX);
+        }
+        bytes.fill_bytes(0);
+        for i in 0..3 {
+            assert_eq!(unsafe { bytes.as_view().read_at::<u32>(i * 4) }, 0);
+        }
+        assert_eq!(bytes.len(), 12);
+    }
+
+    #[test]
+    fn to_vec_of_ranges() {
+        let values: Vec<u32> = (0..300).collect();
+        let bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe {
+            assert_eq!(bytes.to_vec_of::<u32, _>(100..200), &values[100..200]);
+            assert_eq!(bytes.to_vec_of::<u32, _>(..=5), &values[..=5]);
+            assert_eq!(bytes.to_vec_of::<u32, _>(295..), &values[295..]);
+            assert_eq!(bytes.to_vec_of::<u32, _>(..), values);
+            assert!(bytes.to_vec_of::<u32, _>(42..42).is_empty());
+            assert!(bytes.to_vec_of::<u32, _>(300..).is_empty());
+        }
+    }
+
+    #[test]
+    fn to_vec_of_wide() {
+        let values: Vec<Wide> = (0..8).map(|i| wide(i, i * 10)).collect();
+        let bytes = UntypedBytes::from_slice(&values[..]);
+        let range = unsafe { bytes.to_vec_of::<Wide, _>(2..5) };
+        assert_eq!(range, &values[2..5]);
+        assert!((range.as_ptr() as usize).is_multiple_of(mem::align_of::<Wide>()));
+    }
+
+    #[test]
+    #[should_panic(expected = "range 2..4 is out of bounds for 3 elements")]
+    fn to_vec_of_out_of_bounds() {
+        unsafe { UntypedBytes::from_slice([1u16, 2, 3]).to_vec_of::<u16, _>(2..4) };
+    }
+
+    #[test]
+    fn into_iter_as_collects_source() {
+        let values = random_vec(37, 1000);
+        let bytes = UntypedBytes::from_slice(&values[..]);
+        let iter = unsafe { bytes.into_iter_as::<u32>() };
+        assert_eq!(iter.len(), 37);
+        assert_eq!(iter.collect::<Vec<_>>(), values);
+
+        let bytes = UntypedBytes::from_slice(&values[..]);
+        let reversed: Vec<u32> = unsafe { bytes.into_iter_as::<u32>() }.rev().collect();
+        assert!(reversed.iter().eq(values.iter().rev()));
+    }
+
+    #[test]
+    fn into_iter_as_both_ends() {
+        let bytes = UntypedBytes::from_slice([1u64, 2, 3, 4]);
+        let mut iter = unsafe { bytes.into_iter_as::<u64>() };
+        assert_eq!(iter.next(), Some(1));
+        assert_eq!(iter.next_back(), Some(4));
+        assert_eq!(iter.len(), 2);
+        assert_eq!(iter.next_back(), Some(3));
+        assert_eq!(iter.next(), Some(2));
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.next_back(), None);
+        assert_eq!(iter.len(), 0);
+    }
+
+    #[test]
+    fn into_iter_as_wide() {
+        let values: Vec<Wide> = (0..5).map(|i| wide(i, !i)).collect();
+        let bytes = UntypedBytes::from_slice(&values[..]);
+        let collected: Vec<Wide> = unsafe { bytes.into_iter_as() }.collect();
+        assert_eq!(collected, values);
+    }
+
+    // Run under Miri to check the allocation is released when only part of it was consumed.
+    #[test]
+    fn into_iter_as_dropped_half_consumed() {
+        let bytes = UntypedBytes::from_slice(&random_vec(16, 100)[..]);
+        let mut iter = unsafe { bytes.into_iter_as::<u32>() };
+        for _ in 0..8 {
+            iter.next().unwrap();
+        }
+        drop(iter);
+
+        let empty = unsafe { UntypedBytes::new().into_iter_as::<u32>() };
+        assert_eq!(empty.len(), 0);
+    }
+
+    #[test]
+    fn retain_as_even_bytes() {
+        let mut bytes = UntypedBytes::from_slice([1u32, 2, 3, 4, 6, 7, 8]);
+        let mut seen = Vec::new();
+        unsafe {
+            bytes.retain_as::<u32>(|&v| {
+                seen.push(v);
+                v % 2 == 0
+            })
+        };
+        // The predicate sees every element exactly once, in order.
+        assert_eq!(seen, [1, 2, 3, 4, 6, 7, 8]);
+        let mut expected = Vec::new();
+        for value in [2u32, 4, 6, 8].iter() {
+            expected.extend_from_slice(&value.to_ne_bytes());
+        }
+        assert_eq!(unsafe { bytes.as_slice() }, &expected[..]);
+    }
+
+    #[test]
+    fn retain_as_wide_matches_vec() {
+        let values: Vec<Wide> = random_vec(40, 10)
+            .into_iter()
+            .enumerate()
+            .map(|(i, key)| wide(key, i as u32))
+            .collect();
+        let mut expected = values.clone();
+        expected.retain(|w| w.0[0] < 5);
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.retain_as::<Wide>(|w| w.0[0] < 5) };
+        assert_eq!(unsafe { bytes.to_vec_of::<Wide, _>(..) }, expected);
+    }
+
+    #[test]
+    fn map_in_place_doubles() {
+        let values = [-3i32, 0, 7, i32::MAX / 2, -100];
+        let mut bytes = UntypedBytes::from_slice(values);
+        unsafe { bytes.map_in_place::<i32>(|v| v * 2) };
+        let doubled: Vec<i32> = values.iter().map(|v| v * 2).collect();
+        assert_eq!(unsafe { bytes.to_vec_of::<i32, _>(..) }, doubled);
+    }
+
+    #[test]
+    fn map_in_place_wide() {
+        let values: Vec<Wide> = (0..6).map(|i| wide(i, 0)).collect();
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        let mut index = 0;
+        unsafe {
+            bytes.map_in_place::<Wide>(|w| {
+                index += 1;
+                wide(w.0[0] + 100, index)
+            })
+        };
+        let expected: Vec<Wide> = (0..6).map(|i| wide(i + 100, i + 1)).collect();
+        assert_eq!(unsafe { bytes.to_vec_of::<Wide, _>(..) }, expected);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a multiple of the element size")]
+    fn map_in_place_rejects_partial_elements() {
+        let mut bytes = UntypedBytes::from_slice([1u8, 2, 3, 4, 5]);
+        unsafe { bytes.map_in_place::<u32>(|v| v) };
+    }
+
+    #[test]
+    fn sort_as_shuffled_u16s() {
+        let mut shuffled: Vec<u16> = (0..500).map(|i| i * 7).collect();
+        let mut state = 0x1234_5678;
+        for i in (1..shuffled.len()).rev() {
+            shuffled.swap(i, xorshift(&mut state) as usize % (i + 1));
+        }
+        for sort in [
+            UntypedBytes::sort_as::<u16>,
+            UntypedBytes::sort_unstable_as::<u16>,
+        ] {
+            let mut bytes = UntypedBytes::from_slice(&shuffled[..]);
+            unsafe { sort(&mut bytes) };
+            let sorted = unsafe { bytes.to_vec_of::<u16, _>(..) };
+            assert_eq!(sorted.len(), 500);
+            assert!(sorted.windows(2).all(|pair| pair[0] < pair[1]));
+        }
+    }
+
+    #[test]
+    fn binary_search_as_after_sort_as() {
+        let values = random_vec(100, 1000);
+        let mut bytes = UntypedBytes::from_slice(&values[..]);
+        unsafe { bytes.sort_as::<u32>() };
+        let mut sorted = values.clone();
+        sorted.sort();
+        for &value in &values {
+            let index = unsafe { bytes.binary_search_as(&value) }.unwrap();
+            assert_eq!(sorted[index], value);
+        }
+        for value in 0..1000 {
+            match unsafe { bytes.binary_search_as(&value) } {
+                Ok(index) => assert_eq!(sorted[index], value),
+                Err(index) => {
+                    assert_eq!(Err(index), sorted.binary_search(&value));
+                    assert!(index == 0 || sorted[index - 1] < value);
+                    assert!(index == sorted.len() || sorted[index] > value);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn binary_search_as_wide() {
+        let values: Vec<Wide> = (0..20).map(|i| wide(i * 2, 0)).collect();
+        let bytes = UntypedBytes::from_slice(&values[..]);
+        assert_eq!(unsafe { bytes.binary_search_as(&wide(14, 0)) }, Ok(7));
+        assert_eq!(unsafe { bytes.binary_search_as(&wide(15, 0)) }, Err(8));
+        assert_eq!(unsafe { bytes.binary_search_as(&wide(99, 0)) }, Err(20));
+    }
+
+    #[test]
+    fn convert_widening() {
+        let samples = [0.5f64, -1.25, 3.0e10, 1.0e-3];
+        let bytes = UntypedBytes::from_slice(samples);
+        let narrowed = unsafe { bytes.convert(|v: f64| v as f32) };
+        assert_eq!(narrowed.capacity(), 16);
+        let widened = unsafe { narrowed.convert(|v: f32| v as f64) };
+        assert_eq!(widened.capacity(), 32);
+        let expected: Vec<f64> = samples.iter().map(|&v| v as f32 as f64).collect();
+        assert_eq!(unsafe { widened.to_vec_of::<f64, _>(..) }, expected);
+    }
+
+    #[test]
+    fn convert_in_place_narrowing_reuses_allocation() {
+        let indices: Vec<u32> = (0..100).map(|i| i * 600).collect();
+        let bytes = UntypedBytes::from_slice(&indices[..]);
+        let (ptr, capacity) = (bytes.bytes.as_ptr(), bytes.capacity());
+        let narrowed = unsafe { bytes.convert_in_place(|i: u32| i as u16) };
+        assert_eq!(narrowed.bytes.as_ptr(), ptr);
+        assert_eq!(narrowed.capacity(), capacity);
+        assert_eq!(narrowed.len(), 200);
+        let expected: Vec<u16> = indices.iter().map(|&i| i as u16).collect();
+        assert_eq!(unsafe { narrowed.to_vec_of::<u16, _>(..) }, expected);
+    }
+
+    #[test]
+    fn convert_in_place_equal_size_and_widening() {
+        let bytes = UntypedBytes::from_slice([1.0f32, -2.0]);
+        let ptr = bytes.bytes.as_ptr();
+        let converted = unsafe { bytes.convert_in_place(f32::to_bits) };
+        assert_eq!(converted.bytes.as_ptr(), ptr);
+        assert_eq!(
+            unsafe { converted.to_vec_of::<u32, _>(..) },
+            [0x3f80_0000, 0xc000_0000]
+        );
+
+        let widened = unsafe { converted.convert_in_place(|bits: u32| bits as u64) };
+        assert_eq!(
+            unsafe { widened.to_vec_of::<u64, _>(..) },
+            [0x3f80_0000, 0xc000_0000]
+        );
+    }
+
+    #[test]
+    fn try_convert_narrowing() {
+        let small = UntypedBytes::from_slice([0u32, 1, 65535]);
+        let narrowed = unsafe { small.try_convert(|i: u32| u16::try_from(i)) }.unwrap();
+        assert_eq!(unsafe { narrowed.to_vec_of::<u16, _>(..) }, [0, 1, 65535]);
+
+        let large = UntypedBytes::from_slice([0u32, 65536, 1, 70000]);
+        let mut seen = 0;
+        let result = unsafe {
+            large.try_convert(|i: u32| {
+                seen += 1;
+                u16::try_from(i)
+            })
+        };
+        assert!(result.is_err());
+        // Conversion stops at the first failure.
+        assert_eq!(seen, 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "is not a multiple of the element size")]
+    fn convert_rejects_partial_elements() {
+        unsafe { UntypedBytes::from_slice([0u8; 6]).convert(|v: u32| v as u8) };
+    }
+}
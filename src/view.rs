@@ -0,0 +1,210 @@
+use crate::{CastError, UntypedBytes};
+use core::{
+    mem,
+    ops::{Bound, Range, RangeBounds},
+    ptr, slice,
+};
+
+/// A borrowed view of untyped bytes, the borrowed counterpart to `UntypedBytes`.
+///
+/// Like `UntypedBytes::as_slice`, the bytes are safe to `memcpy` but unsafe to inspect in the
+/// presence of padding bytes.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UntypedSlice<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> UntypedSlice<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        UntypedSlice { bytes }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len()
+    }
+
+    /// Returns a sub-view of the given byte range. Panics if the range is out of bounds.
+    pub fn slice<R: RangeBounds<usize>>(&self, range: R) -> UntypedSlice<'a> {
+        UntypedSlice::new(&self.bytes[bounds(range)])
+    }
+
+    /// See `UntypedBytes::as_slice`.
+    pub unsafe fn as_slice(&self) -> &'a [u8] {
+        self.bytes
+    }
+
+    /// Casts the viewed bytes to a value of type `T`. This is only safe if the viewed bytes were
+    /// created from a value of type `T`.
+    pub unsafe fn cast<T: Copy + Send + Sync + 'static>(&self) -> T {
+        debug_assert_eq!(
+            mem::size_of::<T>(),
+            self.len(),
+            "Attempt to cast `UntypedSlice` to a value of a different size"
+        );
+        self.read_at(0)
+    }
+
+    /// Reads a value of type `T` starting at `offset`, regardless of alignment. This is only safe
+    /// if those bytes were created from a value of type `T`. Panics if the read is out of bounds.
+    pub unsafe fn read_at<T: Copy + Send + Sync + 'static>(&self, offset: usize) -> T {
+        let bytes = &self.bytes[offset..offset + mem::size_of::<T>()];
+        ptr::read_unaligned(bytes.as_ptr() as *const T)
+    }
+
+    /// See `UntypedBytes::try_cast_slice`.
+    pub unsafe fn try_cast_slice<T: Copy + Send + Sync + 'static>(
+        &self,
+    ) -> Result<&'a [T], CastError> {
+        let size = mem::size_of::<T>();
+        assert_ne!(size, 0, "elements must not be zero-sized");
+        if !self.len().is_multiple_of(size) {
+            return Err(CastError::NotAMultiple {
+                element_size: size,
+                found: self.len(),
+            });
+        }
+        if self.is_empty() {
+            return Ok(&[]);
+        }
+        let ptr = self.bytes.as_ptr() as *const T;
+        if !ptr.is_aligned() {
+            return Err(CastError::AlignmentMismatch {
+                required: mem::align_of::<T>(),
+                address: ptr as usize,
+            });
+        }
+        Ok(slice::from_raw_parts(ptr, self.len() / size))
+    }
+
+    /// Like `read_at`, but returns an error instead of panicking if the read is out of bounds.
+    pub unsafe fn try_read_at<T: Copy + Send + Sync + 'static>(
+        &self,
+        offset: usize,
+    ) -> Result<T, CastError> {
+        let size = mem::size_of::<T>();
+        match offset.checked_add(size) {
+            Some(end) if end <= self.len() => Ok(self.read_at(offset)),
+            _ => Err(CastError::OutOfBounds {
+                offset,
+                size,
+                len: self.len(),
+            }),
+        }
+    }
+
+    pub fn to_owned(&self) -> UntypedBytes {
+        let mut result = UntypedBytes::with_capacity(self.len());
+        result.extend_from_view(*self);
+        result
+    }
+
+    /// Copies the viewed bytes into `dst`. Panics if the lengths differ.
+    pub fn copy_to_slice(&self, dst: &mut [u8]) {
+        dst.copy_from_slice(self.bytes)
+    }
+}
+
+impl<'a> From<&'a [u8]> for UntypedSlice<'a> {
+    fn from(value: &'a [u8]) -> Self {
+        UntypedSlice::new(value)
+    }
+}
+
+impl<'a> From<&'a UntypedBytes> for UntypedSlice<'a> {
+    fn from(value: &'a UntypedBytes) -> Self {
+        value.as_view()
+    }
+}
+
+pub(crate) fn bounds<R: RangeBounds<usize>>(range: R) -> (Bound<usize>, Bound<usize>) {
+    (range.start_bound().cloned(), range.end_bound().cloned())
+}
+
+// Resolves `range` against a sequence of `len` items. Panics if it is out of bounds or
+// decreasing.
+pub(crate) fn resolve<R: RangeBounds<usize>>(range: R, len: usize) -> Range<usize> {
+    let start = match range.start_bound() {
+        Bound::Included(&start) => start,
+        Bound::Excluded(&start) => start + 1,
+        Bound::Unbounded => 0,
+    };
+    let end = match range.end_bound() {
+        Bound::Included(&end) => end + 1,
+        Bound::Excluded(&end) => end,
+        Bound::Unbounded => len,
+    };
+    assert!(
+        start <= end && end <= len,
+        "range {}..{} is out of bounds for {} elements",
+        start,
+        end,
+        len
+    );
+    start..end
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn numbered() -> UntypedBytes {
+        UntypedBytes::from_slice([0u8, 1, 2, 3, 4, 5, 6, 7, 8, 9])
+    }
+
+    #[test]
+    fn nested_slices_are_relative() {
+        let bytes = numbered();
+        let outer = bytes.view_range(2..8);
+        let inner = outer.slice(1..=3);
+        assert_eq!(outer.len(), 6);
+        assert_eq!(unsafe { inner.as_slice() }, &[3, 4, 5]);
+        assert_eq!(unsafe { inner.slice(1..).as_slice() }, &[4, 5]);
+        assert!(inner.slice(3..).is_empty());
+        assert_eq!(unsafe { outer.slice(..).as_slice() }, unsafe {
+            bytes.view_range(2..8).as_slice()
+        });
+    }
+
+    // The parent has bytes past the end of `outer`, but the sub-slice must not reach them.
+    #[test]
+    #[should_panic]
+    fn nested_slice_past_the_view_panics() {
+        let bytes = numbered();
+        bytes.view_range(2..8).slice(4..7);
+    }
+
+    #[test]
+    #[should_panic]
+    fn view_range_past_the_end_panics() {
+        numbered().view_range(5..11);
+    }
+
+    #[test]
+    fn cast_and_read_at() {
+        let mut bytes = UntypedBytes::from_slice([0xffu8]);
+        bytes.push(0x0102_0304u32);
+        bytes.push(7u16);
+        let view = bytes.as_view();
+        assert_eq!(unsafe { view.read_at::<u32>(1) }, 0x0102_0304);
+        assert_eq!(unsafe { view.slice(5..).cast::<u16>() }, 7);
+    }
+
+    #[test]
+    fn to_owned_and_copy_to_slice() {
+        let bytes = numbered();
+        let view = bytes.view_range(6..);
+        assert_eq!(unsafe { view.to_owned().as_slice() }, &[6, 7, 8, 9]);
+        let mut dst = [0; 4];
+        view.copy_to_slice(&mut dst);
+        assert_eq!(dst, [6, 7, 8, 9]);
+
+        let mut joined = UntypedBytes::from_slice([0xffu8]);
+        joined.extend_from_view(view.slice(2..));
+        joined.extend_from_view(UntypedSlice::from(&b"ab"[..]));
+        assert_eq!(unsafe { joined.as_slice() }, &[0xff, 8, 9, b'a', b'b']);
+    }
+}
@@ -0,0 +1,58 @@
+use crate::UntypedBytes;
+use js_sys::Uint8Array;
+
+impl UntypedBytes {
+    /// Copies the backing bytes into a new JavaScript `Uint8Array`.
+    pub fn to_uint8array(&self) -> Uint8Array {
+        Uint8Array::from(&self.bytes[..])
+    }
+
+    /// Returns a `Uint8Array` viewing the backing bytes directly in wasm memory, without copying.
+    ///
+    /// The view is invalidated by anything that reallocates this buffer or grows the wasm memory,
+    /// including any allocation at all, so it must be consumed before calling back into Rust.
+    pub unsafe fn uint8array_view(&self) -> Uint8Array {
+        Uint8Array::view(&self.bytes)
+    }
+
+    /// Copies the contents of a JavaScript `Uint8Array` into a new buffer.
+    pub fn from_uint8array(array: &Uint8Array) -> Self {
+        Self::from_byte_vec(array.to_vec())
+    }
+}
+
+#[cfg(all(test, target_arch = "wasm32"))]
+mod tests {
+    use super::*;
+    use wasm_bindgen_test::wasm_bindgen_test;
+
+    fn pattern() -> UntypedBytes {
+        let mut bytes = UntypedBytes::from_slice([1.5f32, -2.0]);
+        bytes.extend_from_slice([0u8, 0x7f, 0x80, 0xff]);
+        bytes
+    }
+
+    #[wasm_bindgen_test]
+    fn round_trip_through_copy() {
+        let bytes = pattern();
+        let array = bytes.to_uint8array();
+        assert_eq!(array.length() as usize, bytes.len());
+        assert_eq!(array.get_index(9), 0x7f);
+        let back = UntypedBytes::from_uint8array(&array);
+        assert_eq!(back.bytes, bytes.bytes);
+    }
+
+    #[wasm_bindgen_test]
+    fn round_trip_through_view() {
+        let bytes = pattern();
+        let back = UntypedBytes::from_uint8array(&unsafe { bytes.uint8array_view() });
+        assert_eq!(back.bytes, bytes.bytes);
+    }
+
+    #[wasm_bindgen_test]
+    fn empty_round_trip() {
+        let array = UntypedBytes::new().to_uint8array();
+        assert_eq!(array.length(), 0);
+        assert!(UntypedBytes::from_uint8array(&array).is_empty());
+    }
+}
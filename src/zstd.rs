@@ -0,0 +1,233 @@
+// zstd compression. The compressed form is the uncompressed length as a little-endian `u64`,
+// followed by a single zstd frame, so decompression can allocate exactly once.
+
+use crate::UntypedBytes;
+use std::{
+    convert::{TryFrom, TryInto},
+    error::Error,
+    fmt,
+    io::Cursor,
+};
+
+const LEN_SIZE: usize = 8;
+
+/// The error returned when decompressing bytes that were not produced by `UntypedBytes::compress`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecompressError {
+    /// The input is too short to contain the uncompressed length and a zstd frame.
+    Truncated { len: usize },
+    /// The uncompressed length could not be allocated.
+    TooLarge { len: u64 },
+    /// The decompressed data did not match the embedded length.
+    LengthMismatch { expected: usize, found: usize },
+    /// The compressed data is corrupt, as reported by zstd.
+    Corrupt { reason: &'static str },
+}
+
+impl fmt::Display for DecompressError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DecompressError::Truncated { len } => write!(
+                f,
+                "truncated input: {} bytes is too short for a {} byte length prefix and a frame",
+                len, LEN_SIZE
+            ),
+            DecompressError::TooLarge { len } => {
+                write!(f, "uncompressed length {} could not be allocated", len)
+            }
+            DecompressError::LengthMismatch { expected, found } => write!(
+                f,
+                "length mismatch: expected {} decompressed bytes, found {}",
+                expected, found
+            ),
+            DecompressError::Corrupt { reason } => write!(f, "corrupt input: {}", reason),
+        }
+    }
+}
+
+impl Error for DecompressError {}
+
+impl UntypedBytes {
+    /// Compresses the backing bytes with zstd at `level` into a new buffer.
+    pub fn compress(&self, level: i32) -> UntypedBytes {
+        let mut out = UntypedBytes::new();
+        self.compress_into(level, &mut out);
+        out
+    }
+
+    /// Like `compress`, but appends the compressed bytes to `out`, reusing its allocation.
+    pub fn compress_into(&self, level: i32, out: &mut UntypedBytes) {
+        out.reserve(LEN_SIZE + zstd_safe::compress_bound(self.len()));
+        out.bytes
+            .extend_from_slice(&(self.len() as u64).to_le_bytes());
+        let start = out.len();
+        let mut cursor = Cursor::new(&mut out.bytes);
+        cursor.set_position(start as u64);
+        zstd_safe::compress(&mut cursor, &self.bytes, level)
+            .expect("zstd compression into a buffer of `compress_bound` bytes failed");
+    }
+
+    /// Decompresses bytes produced by `compress` into a new buffer.
+    pub fn decompress(&self) -> Result<UntypedBytes, DecompressError> {
+        let mut out = UntypedBytes::new();
+        self.decompress_into(&mut out)?;
+        Ok(out)
+    }
+
+    /// Like `decompress`, but appends the decompressed bytes to `out`, reusing its allocation. On
+    /// error `out` is left unchanged.
+    pub fn decompress_into(&self, out: &mut UntypedBytes) -> Result<(), DecompressError> {
+        let (len, frame) = self
+            .bytes
+            .split_at_checked(LEN_SIZE)
+            .filter(|(_, frame)| !frame.is_empty())
+            .ok_or(DecompressError::Truncated { len: self.len() })?;
+        let len = u64::from_le_bytes(len.try_into().unwrap());
+        let expected = usize::try_from(len)
+            .ok()
+            .filter(|&expected| out.bytes.try_reserve_exact(expected).is_ok())
+            .ok_or(DecompressError::TooLarge { len })?;
+        let start = out.len();
+        let mut cursor = Cursor::new(&mut out.bytes);
+        cursor.set_position(start as u64);
+        match zstd_safe::decompress(&mut cursor, frame) {
+            Ok(found) if found == expected => Ok(()),
+            Ok(found) => {
+                out.truncate_bytes(start);
+                Err(DecompressError::LengthMismatch { expected, found })
+            }
+            Err(code) => Err(DecompressError::Corrupt {
+                reason: zstd_safe::get_error_name(code),
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Deterministic incompressible bytes.
+    fn random_bytes(len: usize) -> UntypedBytes {
+        let mut state = 0x2545_f491u32;
+        let mut bytes = UntypedBytes::with_capacity(len);
+        for _ in 0..len {
+            state ^= state << 13;
+            state ^= state >> 17;
+            state ^= state << 5;
+            bytes.push(state as u8);
+        }
+        bytes
+    }
+
+    fn repetitive(len: usize) -> UntypedBytes {
+        let mut bytes = UntypedBytes::with_capacity(len);
+        for i in 0..len {
+            bytes.push(b"vertex"[i % 6]);
+        }
+        bytes
+    }
+
+    fn assert_round_trip(bytes: &UntypedBytes) -> UntypedBytes {
+        let compressed = bytes.compress(3);
+        let decompressed = compressed.decompress().unwrap();
+        assert_eq!(unsafe { decompressed.as_slice() }, unsafe {
+            bytes.as_slice()
+        });
+        assert_eq!(decompressed.capacity(), bytes.len());
+        compressed
+    }
+
+    #[test]
+    fn random_round_trip() {
+        let bytes = random_bytes(100_000);
+        let compressed = assert_round_trip(&bytes);
+        assert!(compressed.len() >= bytes.len());
+    }
+
+    #[test]
+    fn repetitive_round_trip() {
+        let bytes = repetitive(100_000);
+        let compressed = assert_round_trip(&bytes);
+        assert!(compressed.len() < bytes.len() / 100);
+    }
+
+    #[test]
+    fn empty_round_trip() {
+        assert_round_trip(&UntypedBytes::new());
+    }
+
+    #[test]
+    fn into_variants_append() {
+        let bytes = repetitive(5000);
+        let mut compressed = UntypedBytes::new();
+        bytes.compress_into(1, &mut compressed);
+        assert_eq!(unsafe { compressed.as_slice() }, unsafe {
+            bytes.compress(1).as_slice()
+        });
+
+        let mut out = UntypedBytes::from_slice(*b"head");
+        compressed.decompress_into(&mut out).unwrap();
+        assert_eq!(out.len(), 4 + bytes.len());
+        assert_eq!(unsafe { &out.as_slice()[..4] }, b"head");
+        assert_eq!(unsafe { &out.as_slice()[4..] }, unsafe { bytes.as_slice() });
+    }
+
+    #[test]
+    fn truncated_input_is_an_error() {
+        let mut compressed = random_bytes(1000).compress(3);
+        let len = compressed.len();
+        compressed.truncate_bytes(len - 10);
+        assert!(matches!(
+            compressed.decompress(),
+            Err(DecompressError::Corrupt { .. })
+        ));
+        compressed.truncate_bytes(LEN_SIZE);
+        assert_eq!(
+            compressed.decompress().unwrap_err(),
+            DecompressError::Truncated { len: LEN_SIZE }
+        );
+        assert_eq!(
+            UntypedBytes::new().decompress().unwrap_err(),
+            DecompressError::Truncated { len: 0 }
+        );
+    }
+
+    #[test]
+    fn corrupt_input_is_an_error() {
+        let bytes = repetitive(1000);
+        let compressed = bytes.compress(3);
+
+        // A length prefix that disagrees with the frame.
+        let mut wrong_len = UntypedBytes::from_slice(&500u64.to_le_bytes()[..]);
+        wrong_len.extend_from_slice(unsafe { &compressed.as_slice()[LEN_SIZE..] });
+        assert!(wrong_len.decompress().is_err());
+        let mut wrong_len = UntypedBytes::from_slice(&2000u64.to_le_bytes()[..]);
+        wrong_len.extend_from_slice(unsafe { &compressed.as_slice()[LEN_SIZE..] });
+        assert_eq!(
+            wrong_len.decompress().unwrap_err(),
+            DecompressError::LengthMismatch {
+                expected: 2000,
+                found: 1000
+            }
+        );
+
+        // A length that cannot be allocated.
+        let mut too_large = UntypedBytes::from_slice(&u64::MAX.to_le_bytes()[..]);
+        too_large.extend_from_slice(unsafe { &compressed.as_slice()[LEN_SIZE..] });
+        assert_eq!(
+            too_large.decompress().unwrap_err(),
+            DecompressError::TooLarge { len: u64::MAX }
+        );
+
+        // A garbage frame, which must leave the output untouched.
+        let mut garbage = UntypedBytes::from_slice(&1000u64.to_le_bytes()[..]);
+        garbage.extend_from_slice(*b"definitely not a zstd frame");
+        let mut out = UntypedBytes::from_slice(*b"keep");
+        assert!(matches!(
+            garbage.decompress_into(&mut out),
+            Err(DecompressError::Corrupt { .. })
+        ));
+        assert_eq!(unsafe { out.as_slice() }, b"keep");
+    }
+}
@@ -0,0 +1,100 @@
+#![cfg(feature = "derive")]
+
+use std::mem;
+use untyped_bytes::{Element, PushFields, UntypedBytes};
+
+#[derive(Clone, Copy, PushFields)]
+#[repr(C)]
+struct Instance {
+    id: u8,
+    position: [f32; 3],
+    flags: u16,
+}
+
+#[derive(PushFields)]
+struct Draw {
+    instance: Instance,
+    #[push(skip)]
+    _debug_name: &'static str,
+    #[push(align = 16)]
+    count: u32,
+}
+
+#[derive(PushFields)]
+struct Pair(u8, u32);
+
+fn packed(value: &impl PushFields) -> Vec<u8> {
+    let mut bytes = UntypedBytes::new();
+    value.push_fields(&mut bytes);
+    unsafe { bytes.as_slice() }.to_vec()
+}
+
+const INSTANCE: Instance = Instance {
+    id: 7,
+    position: [1.0, 2.0, 3.0],
+    flags: 0xbeef,
+};
+
+fn instance_bytes() -> Vec<u8> {
+    let mut expected = vec![7];
+    for value in &[1.0f32, 2.0, 3.0] {
+        expected.extend_from_slice(&value.to_ne_bytes());
+    }
+    expected.extend_from_slice(&0xbeefu16.to_ne_bytes());
+    expected
+}
+
+#[test]
+fn packed_size_differs_from_size_of() {
+    assert_eq!(Instance::packed_size(), 15);
+    assert_eq!(mem::size_of::<Instance>(), 20);
+    assert_eq!(packed(&INSTANCE), instance_bytes());
+}
+
+#[test]
+fn nested_struct_with_skip_and_align() {
+    let draw = Draw {
+        instance: INSTANCE,
+        _debug_name: "grid",
+        count: 42,
+    };
+    let mut expected = instance_bytes();
+    expected.push(0);
+    expected.extend_from_slice(&42u32.to_ne_bytes());
+    assert_eq!(Draw::packed_size(), 20);
+    assert_eq!(packed(&draw), expected);
+}
+
+#[test]
+fn tuple_struct() {
+    assert_eq!(Pair::packed_size(), 5);
+    let mut expected = vec![1];
+    expected.extend_from_slice(&2u32.to_ne_bytes());
+    assert_eq!(packed(&Pair(1, 2)), expected);
+}
+
+#[derive(Clone, Copy, Element)]
+#[repr(C)]
+struct Vertex {
+    position: [f32; 3],
+    color: u32,
+}
+
+#[derive(Clone, Copy, Element)]
+struct Wrapper<T>(T);
+
+#[test]
+fn element_extends_from_iterators() {
+    let mut bytes = UntypedBytes::new();
+    bytes.extend((0..4).map(|i| Vertex {
+        position: [i as f32; 3],
+        color: 0xffff_ffff,
+    }));
+    assert_eq!(bytes.len(), 4 * mem::size_of::<Vertex>());
+    assert_eq!(unsafe { bytes.as_view().read_at::<f32>(16) }, 1.0);
+
+    bytes.clear();
+    bytes.extend([Wrapper(1u16), Wrapper(2)].iter().copied());
+    assert_eq!(bytes.len(), 4);
+    assert_eq!(unsafe { bytes.as_view().read_at::<u16>(2) }, 2);
+}
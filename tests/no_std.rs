@@ -0,0 +1,38 @@
+// The core API with only `core` and `alloc` in scope, as a `no_std` crate sees it. CI runs this
+// with `--no-default-features`.
+#![no_std]
+
+extern crate alloc;
+
+use alloc::vec;
+use core::mem;
+use untyped_bytes::{InlineUntypedBytes, UntypedBytes};
+
+#[test]
+fn push_and_cast() {
+    let mut bytes = UntypedBytes::new();
+    bytes.push([1u32, 2]);
+    assert_eq!(bytes.len(), 8);
+    assert_eq!(unsafe { bytes.cast::<[u32; 2]>() }, [1, 2]);
+    assert!(unsafe { bytes.try_cast::<u32>() }.is_err());
+}
+
+#[test]
+fn extend_and_read_back() {
+    let mut bytes = UntypedBytes::with_capacity(mem::size_of::<[f32; 2]>() * 3);
+    bytes.extend_from_slice([[0.0f32, 1.0], [2.0, 3.0]]);
+    bytes.extend((4..6).map(|i| [i as f32; 2]));
+    assert_eq!(
+        unsafe { bytes.to_vec_of::<[f32; 2], _>(..) },
+        vec![[0.0, 1.0], [2.0, 3.0], [4.0, 4.0], [5.0, 5.0]]
+    );
+}
+
+#[test]
+fn inline_without_allocating() {
+    let mut bytes = InlineUntypedBytes::<8>::new();
+    bytes.push(1u32);
+    assert_eq!(bytes.try_push(2u32), Ok(()));
+    assert_eq!(bytes.try_push(3u8), Err(3));
+    assert_eq!(unsafe { bytes.cast::<[u32; 2]>() }, [1, 2]);
+}
@@ -0,0 +1,6 @@
+#[cfg(feature = "safe")]
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,14 @@
+use untyped_bytes::PodBytes;
+
+// `a` is followed by three bytes of padding, so `Padded` is not `bytemuck::NoUninit`.
+#[repr(C)]
+#[derive(Clone, Copy)]
+struct Padded {
+    a: u8,
+    b: u32,
+}
+
+fn main() {
+    let mut bytes = PodBytes::new();
+    bytes.push(Padded { a: 1, b: 2 });
+}
@@ -0,0 +1,6 @@
+use untyped_bytes::PodBytes;
+
+fn main() {
+    let bytes = PodBytes::from_slice([2u8]);
+    let _: bool = bytes.cast();
+}
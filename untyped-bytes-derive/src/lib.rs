@@ -0,0 +1,168 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{quote, quote_spanned};
+use syn::{
+    parse_macro_input, parse_quote, spanned::Spanned, Data, DeriveInput, Fields, Index, LitInt,
+};
+
+/// Derives `untyped_bytes::PushFields`, writing each field in declaration order with no padding
+/// between fields.
+///
+/// Fields accept `#[push(skip)]` to leave them out entirely, and `#[push(align = N)]` to zero pad
+/// up to a multiple of `N` bytes (measured from the start of the struct) before the field is
+/// written.
+#[proc_macro_derive(PushFields, attributes(push))]
+pub fn derive_push_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `untyped_bytes::Element`, so iterators over the type can be passed to
+/// `UntypedBytes::extend`. The type must be `Copy + Send + Sync + 'static`, and each type
+/// parameter is required to be an `Element` as well.
+#[proc_macro_derive(Element)]
+pub fn derive_element(input: TokenStream) -> TokenStream {
+    let mut input = parse_macro_input!(input as DeriveInput);
+    let params: Vec<_> = input
+        .generics
+        .type_params()
+        .map(|param| param.ident.clone())
+        .collect();
+    let where_clause = input.generics.make_where_clause();
+    for param in params {
+        where_clause
+            .predicates
+            .push(parse_quote!(#param: ::untyped_bytes::Element));
+    }
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    quote! {
+        impl #impl_generics ::untyped_bytes::Element for #name #ty_generics #where_clause {}
+    }
+    .into()
+}
+
+struct FieldAttrs {
+    skip: bool,
+    align: Option<usize>,
+}
+
+fn field_attrs(field: &syn::Field) -> syn::Result<FieldAttrs> {
+    let mut result = FieldAttrs {
+        skip: false,
+        align: None,
+    };
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("push"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                result.skip = true;
+                Ok(())
+            } else if meta.path.is_ident("align") {
+                let lit: LitInt = meta.value()?.parse()?;
+                let align: usize = lit.base10_parse()?;
+                if !align.is_power_of_two() {
+                    return Err(syn::Error::new(
+                        lit.span(),
+                        "`align` must be a power of two",
+                    ));
+                }
+                result.align = Some(align);
+                Ok(())
+            } else {
+                Err(meta.error("expected `skip` or `align = N`"))
+            }
+        })?;
+    }
+    Ok(result)
+}
+
+fn expand(mut input: DeriveInput) -> syn::Result<TokenStream2> {
+    let fields = match &input.data {
+        Data::Struct(data) => &data.fields,
+        _ => {
+            return Err(syn::Error::new(
+                input.span(),
+                "`PushFields` can only be derived for structs",
+            ))
+        }
+    };
+
+    let mut pushes = Vec::new();
+    let mut sizes = Vec::new();
+    let mut bounds = Vec::new();
+    let members: Vec<_> = match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .map(|field| {
+                let ident = field.ident.as_ref().unwrap();
+                (field, quote!(#ident))
+            })
+            .collect(),
+        Fields::Unnamed(unnamed) => unnamed
+            .unnamed
+            .iter()
+            .enumerate()
+            .map(|(i, field)| {
+                let index = Index::from(i);
+                (field, quote!(#index))
+            })
+            .collect(),
+        Fields::Unit => Vec::new(),
+    };
+    for (field, member) in members {
+        let attrs = field_attrs(field)?;
+        if attrs.skip {
+            continue;
+        }
+        let ty = &field.ty;
+        let span = ty.span();
+        if let Some(align) = attrs.align {
+            pushes.push(quote! {
+                let written = out.len() - start;
+                let padding = ::untyped_bytes::align_up(written, #align) - written;
+                out.extend((0..padding).map(|_| 0u8));
+            });
+            sizes.push(quote! {
+                size = ::untyped_bytes::align_up(size, #align);
+            });
+        }
+        pushes.push(quote_spanned! {span=>
+            ::untyped_bytes::PushFields::push_fields(&self.#member, out);
+        });
+        sizes.push(quote_spanned! {span=>
+            size += <#ty as ::untyped_bytes::PushFields>::packed_size();
+        });
+        bounds.push(quote_spanned! {span=> #ty: ::untyped_bytes::PushFields });
+    }
+
+    let where_clause = input.generics.make_where_clause();
+    for bound in bounds {
+        where_clause.predicates.push(parse_quote!(#bound));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    Ok(quote! {
+        impl #impl_generics ::untyped_bytes::PushFields for #name #ty_generics #where_clause {
+            #[allow(unused_variables)]
+            fn push_fields(&self, out: &mut ::untyped_bytes::UntypedBytes) {
+                let start = out.len();
+                #(#pushes)*
+            }
+
+            #[allow(unused_mut)]
+            fn packed_size() -> usize {
+                let mut size = 0usize;
+                #(#sizes)*
+                size
+            }
+        }
+    })
+}